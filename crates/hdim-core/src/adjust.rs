@@ -0,0 +1,199 @@
+use crate::state::AdjustState;
+use image::{DynamicImage, Rgba32FImage};
+use palette::{FromColor, LinSrgb, Oklch, ShiftHue, Srgb};
+use std::fmt;
+
+/// One of the values of an `AdjustState`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Adjustment {
+    Brightness,
+    Contrast,
+    Saturation,
+    Hue,
+    Gamma,
+    Exposure,
+}
+
+/// The Oklab lightness of sRGB middle gray, which contrast spreads the lightness around.
+const MIDDLE_GRAY: f32 = 0.6;
+
+impl Adjustment {
+    /// Every adjustment, in the order they are applied.
+    pub const ALL: [Adjustment; 6] = [
+        Adjustment::Exposure,
+        Adjustment::Gamma,
+        Adjustment::Brightness,
+        Adjustment::Contrast,
+        Adjustment::Saturation,
+        Adjustment::Hue,
+    ];
+
+    /// The smallest and the largest value.
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            Adjustment::Brightness | Adjustment::Contrast | Adjustment::Saturation => {
+                (-100.0, 100.0)
+            }
+            Adjustment::Hue => (-180.0, 180.0),
+            Adjustment::Gamma => (0.1, 5.0),
+            Adjustment::Exposure => (-5.0, 5.0),
+        }
+    }
+
+    /// How much one key press changes the value, or a fine one with `fine`.
+    pub fn step(self, fine: bool) -> f32 {
+        match (self, fine) {
+            (Adjustment::Gamma, false) => 0.1,
+            (Adjustment::Gamma, true) => 0.01,
+            (Adjustment::Exposure, false) => 0.25,
+            (Adjustment::Exposure, true) => 0.05,
+            (Adjustment::Hue, false) => 10.0,
+            (_, false) => 5.0,
+            (_, true) => 1.0,
+        }
+    }
+
+    /// `value` with the unit of this adjustment, e.g. `+1.50 EV`.
+    pub fn format_value(self, value: f32) -> String {
+        match self {
+            Adjustment::Brightness | Adjustment::Contrast | Adjustment::Saturation => {
+                format!("{:+}", value)
+            }
+            Adjustment::Hue => format!("{:+}°", value),
+            Adjustment::Gamma => format!("{:.2}", value),
+            Adjustment::Exposure => format!("{:+.2} EV", value),
+        }
+    }
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Adjustment::Brightness => write!(f, "Brightness"),
+            Adjustment::Contrast => write!(f, "Contrast"),
+            Adjustment::Saturation => write!(f, "Saturation"),
+            Adjustment::Hue => write!(f, "Hue"),
+            Adjustment::Gamma => write!(f, "Gamma"),
+            Adjustment::Exposure => write!(f, "Exposure"),
+        }
+    }
+}
+
+impl AdjustState {
+    pub fn get(&self, adjustment: Adjustment) -> f32 {
+        match adjustment {
+            Adjustment::Brightness => self.brightness,
+            Adjustment::Contrast => self.contrast,
+            Adjustment::Saturation => self.saturation,
+            Adjustment::Hue => self.hue,
+            Adjustment::Gamma => self.gamma,
+            Adjustment::Exposure => self.exposure,
+        }
+    }
+
+    /// Sets a value, clamped to the range of the adjustment.
+    pub fn set(&mut self, adjustment: Adjustment, value: f32) {
+        let (min, max) = adjustment.range();
+        let value = value.clamp(min, max);
+        match adjustment {
+            Adjustment::Brightness => self.brightness = value,
+            Adjustment::Contrast => self.contrast = value,
+            Adjustment::Saturation => self.saturation = value,
+            Adjustment::Hue => self.hue = value,
+            Adjustment::Gamma => self.gamma = value,
+            Adjustment::Exposure => self.exposure = value,
+        }
+    }
+
+    /// Changes a value by `steps` key presses, fine ones with `fine`.
+    pub fn nudge(&mut self, adjustment: Adjustment, steps: i32, fine: bool) {
+        let value = self.get(adjustment) + steps as f32 * adjustment.step(fine);
+        // Snaps to the fine steps, repeated float additions drift away from them
+        let fine_step = adjustment.step(true);
+        self.set(adjustment, (value / fine_step).round() * fine_step);
+    }
+
+    /// Whether applying the adjustments would leave every pixel as it is.
+    pub fn is_neutral(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The adjustments that differ from neutral, in the order they are applied.
+    pub fn changed(&self) -> impl Iterator<Item = Adjustment> + '_ {
+        let neutral = Self::default();
+        Adjustment::ALL
+            .into_iter()
+            .filter(move |&adjustment| self.get(adjustment) != neutral.get(adjustment))
+    }
+}
+
+impl fmt::Display for AdjustState {
+    /// Lists the changed adjustments, e.g. `Gamma 1.20, Hue +30°`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, adjustment) in self.changed().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            let value = adjustment.format_value(self.get(adjustment));
+            write!(f, "{} {}", adjustment, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies the tonal adjustments in `state` to `image`.
+///
+/// Exposure scales the linear light, gamma bends the sRGB values, and the
+/// rest work on Oklch lightness, chroma and hue so that they keep the
+/// perceived color. Alpha is left alone. The result keeps the bit depth of
+/// `image` and whether it has an alpha channel.
+pub fn adjust(image: &DynamicImage, state: &AdjustState) -> DynamicImage {
+    if state.is_neutral() {
+        return image.clone();
+    }
+    let exposure = state.exposure.exp2();
+    let inverse_gamma = 1.0 / state.gamma;
+    let perceptual = state.brightness != 0.0
+        || state.contrast != 0.0
+        || state.saturation != 0.0
+        || state.hue != 0.0;
+
+    let mut pixels: Rgba32FImage = image.to_rgba32f();
+    for pixel in pixels.pixels_mut() {
+        let [r, g, b, _] = pixel.0;
+        let mut color = Srgb::new(r, g, b);
+        if state.exposure != 0.0 {
+            let linear: LinSrgb = color.into_linear() * exposure;
+            color = Srgb::from_linear(linear);
+        }
+        if state.gamma != 1.0 {
+            let [r, g, b] =
+                [color.red, color.green, color.blue].map(|c| c.max(0.0).powf(inverse_gamma));
+            color = Srgb::new(r, g, b);
+        }
+        if perceptual {
+            let mut lch = Oklch::from_color(color);
+            lch.l += state.brightness / 100.0;
+            lch.l = (lch.l - MIDDLE_GRAY) * (1.0 + state.contrast / 100.0) + MIDDLE_GRAY;
+            lch.l = lch.l.max(0.0);
+            lch.chroma *= 1.0 + state.saturation / 100.0;
+            color = Srgb::from_color(lch.shift_hue(state.hue));
+        }
+        let (r, g, b) = color.into_components();
+        pixel.0[..3].copy_from_slice(&[r, g, b].map(|c| c.clamp(0.0, 1.0)));
+    }
+
+    let adjusted = DynamicImage::ImageRgba32F(pixels);
+    let color = image.color();
+    match (
+        color.bytes_per_pixel() / color.channel_count(),
+        color.has_alpha(),
+    ) {
+        (1, true) => DynamicImage::ImageRgba8(adjusted.to_rgba8()),
+        (1, false) => DynamicImage::ImageRgb8(adjusted.to_rgb8()),
+        (2, true) => DynamicImage::ImageRgba16(adjusted.to_rgba16()),
+        (2, false) => DynamicImage::ImageRgb16(adjusted.to_rgb16()),
+        (_, true) => adjusted,
+        (_, false) => DynamicImage::ImageRgb32F(adjusted.to_rgb32f()),
+    }
+}
@@ -0,0 +1,64 @@
+use crate::error::HdimError;
+use crate::state::CropState;
+use crate::{HdimImage, Size, viewport_source_size};
+use image::{DynamicImage, GenericImageView};
+
+/// Crops `image` by the margins in `crop`, returning a new `HdimImage`.
+///
+/// The path and format of the source are kept so the result can be saved
+/// alongside the original.
+pub fn crop(image: &HdimImage, crop: &CropState) -> Result<HdimImage, HdimError> {
+    Ok(image.with_data(crop_image(&image.data, crop)?))
+}
+
+/// Crops a `DynamicImage` by the margins in `crop`.
+///
+/// Fails with `HdimError::EmptyCrop` if the margins meet or overlap in either direction.
+pub fn crop_image(image: &DynamicImage, crop: &CropState) -> Result<DynamicImage, HdimError> {
+    let (x, y, width, height) = crop_rect(image.dimensions(), crop)?;
+    Ok(image.crop_imm(x, y, width, height))
+}
+
+/// The area of an image of `(width, height)` pixels that `crop` keeps, as `(x, y, width, height)`.
+pub(crate) fn crop_rect(
+    (width, height): (u32, u32),
+    crop: &CropState,
+) -> Result<(u32, u32, u32, u32), HdimError> {
+    let horizontal = crop.left.saturating_add(crop.right);
+    let vertical = crop.top.saturating_add(crop.bottom);
+
+    if horizontal >= width || vertical >= height {
+        return Err(HdimError::EmptyCrop {
+            left: crop.left,
+            right: crop.right,
+            top: crop.top,
+            bottom: crop.bottom,
+            width,
+            height,
+        });
+    }
+
+    Ok((crop.left, crop.top, width - horizontal, height - vertical))
+}
+
+impl CropState {
+    /// Builds the margins that crop an image of size `image` down to the area visible in a viewport.
+    ///
+    /// `source_pos` is the top-left pixel shown, `zoom` is `source_pixels / terminal_characters`
+    /// and `viewport` is the size of the viewport in terminal cells. Parts of the viewport that
+    /// lie outside the image produce no margin.
+    pub fn from_viewport(image: Size, source_pos: (u32, u32), zoom: f32, viewport: Size) -> Self {
+        let visible = viewport_source_size(viewport, zoom);
+        let left = source_pos.0.min(image.width);
+        let top = source_pos.1.min(image.height);
+        let right_edge = left.saturating_add(visible.width).min(image.width);
+        let bottom_edge = top.saturating_add(visible.height).min(image.height);
+
+        CropState {
+            left,
+            right: image.width - right_edge,
+            top,
+            bottom: image.height - bottom_edge,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+/// Errors produced by the image operations in `hdim-core`.
+#[derive(Debug, Error)]
+pub enum HdimError {
+    /// The crop margins would remove every pixel in at least one direction.
+    #[error(
+        "crop margins ({left}, {right}, {top}, {bottom}) leave nothing of a {width}x{height} image"
+    )]
+    EmptyCrop {
+        left: u32,
+        right: u32,
+        top: u32,
+        bottom: u32,
+        width: u32,
+        height: u32,
+    },
+    /// A resize was requested with a zero width or height.
+    #[error("cannot resize to {width}x{height}")]
+    EmptyResize { width: u32, height: u32 },
+    /// A resize would create more than `resize::MAX_PIXELS` pixels.
+    #[error("resizing to {width}x{height} would need too much memory")]
+    ResizeTooLarge { width: u32, height: u32 },
+    /// A rotation angle that is infinite or not a number.
+    #[error("cannot rotate by {0} degrees")]
+    InvalidAngle(f32),
+    /// A free rotation would create a canvas of more than `resize::MAX_PIXELS` pixels.
+    #[error("rotating to a {width}x{height} canvas would need too much memory")]
+    RotationTooLarge { width: u32, height: u32 },
+    /// The format of the image could not be determined, so it cannot be saved.
+    #[error("unable to determine the image format for {0}")]
+    UnknownFormat(std::path::PathBuf),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -20,7 +20,7 @@ pub fn get_exposure_exif(exif: &Exif) -> Option<ExposureExif> {
         f_number: get_rational(exif.get_field(Tag::FNumber, In::PRIMARY)),
         iso: exif
             .get_field(Tag::ISOSpeed, In::PRIMARY)
-            .and_then(|f| f.value.get_uint(0).map(|v| v as u32)),
+            .and_then(|f| f.value.get_uint(0)),
         exposure_bias: get_rational(exif.get_field(Tag::ExposureBiasValue, In::PRIMARY)),
         metering_mode: exif
             .get_field(Tag::MeteringMode, In::PRIMARY)
@@ -17,7 +17,7 @@ pub fn get_gps_exif(exif: &Exif) -> Option<GpsExif> {
         altitude: get_rational(exif.get_field(Tag::GPSAltitude, In::PRIMARY)),
         timestamp: get_rational_vec(exif.get_field(Tag::GPSTimeStamp, In::PRIMARY)).map(|v| {
             (
-                v.get(0).map_or(0, |&f| f as u8),
+                v.first().map_or(0, |&f| f as u8),
                 v.get(1).map_or(0, |&f| f as u8),
                 v.get(2).map_or(0, |&f| f as u8),
             )
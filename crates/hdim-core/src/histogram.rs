@@ -0,0 +1,156 @@
+use crate::crop::crop_rect;
+use crate::error::HdimError;
+use crate::state::CropState;
+use image::{DynamicImage, GenericImageView};
+use std::fmt;
+
+/// A channel of a `Histogram`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+    /// Rec. 709 luma of the sRGB values.
+    Luminance,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 4] = [
+        Channel::Red,
+        Channel::Green,
+        Channel::Blue,
+        Channel::Luminance,
+    ];
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Red => write!(f, "R"),
+            Channel::Green => write!(f, "G"),
+            Channel::Blue => write!(f, "B"),
+            Channel::Luminance => write!(f, "L"),
+        }
+    }
+}
+
+/// Counts of the 8-bit values of every channel.
+///
+/// Images with more bits per channel are rounded to 8 bits. Fully
+/// transparent pixels are not counted, as they are never seen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// Indexed like `Channel::ALL`.
+    bins: [[u64; 256]; 4],
+    /// The number of counted pixels.
+    pub pixels: u64,
+}
+
+/// Statistics of one channel of a `Histogram`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChannelStats {
+    pub min: u8,
+    pub max: u8,
+    pub mean: f32,
+    pub median: u8,
+    /// Percentage of the pixels at 0, where shadow detail is lost.
+    pub clipped_shadows: f32,
+    /// Percentage of the pixels at 255, where highlight detail is lost.
+    pub clipped_highlights: f32,
+}
+
+impl Histogram {
+    /// The histogram of the whole image.
+    pub fn new(image: &DynamicImage) -> Self {
+        let mut histogram = Self::empty();
+        match image {
+            DynamicImage::ImageRgb8(buffer) => {
+                for pixel in buffer.pixels() {
+                    let [r, g, b] = pixel.0;
+                    histogram.add([r, g, b, 255]);
+                }
+            }
+            DynamicImage::ImageRgba8(buffer) => {
+                for pixel in buffer.pixels() {
+                    histogram.add(pixel.0);
+                }
+            }
+            other => {
+                for pixel in other.to_rgba8().pixels() {
+                    histogram.add(pixel.0);
+                }
+            }
+        }
+        histogram
+    }
+
+    /// The histogram of the part of `image` that `crop` keeps.
+    ///
+    /// Fails like `crop_image` if the margins leave nothing.
+    pub fn of_crop(image: &DynamicImage, crop: &CropState) -> Result<Self, HdimError> {
+        if *crop == CropState::default() {
+            return Ok(Self::new(image));
+        }
+        let (x, y, width, height) = crop_rect(image.dimensions(), crop)?;
+        let mut histogram = Self::empty();
+        for (_, _, pixel) in image.view(x, y, width, height).pixels() {
+            histogram.add(pixel.0);
+        }
+        Ok(histogram)
+    }
+
+    fn empty() -> Self {
+        Self {
+            bins: [[0; 256]; 4],
+            pixels: 0,
+        }
+    }
+
+    fn add(&mut self, [r, g, b, a]: [u8; 4]) {
+        if a == 0 {
+            return;
+        }
+        let luma = 0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32;
+        for (bins, value) in self.bins.iter_mut().zip([r, g, b, luma.round() as u8]) {
+            bins[value as usize] += 1;
+        }
+        self.pixels += 1;
+    }
+
+    /// The number of pixels with each value of `channel`.
+    pub fn bins(&self, channel: Channel) -> &[u64; 256] {
+        &self.bins[channel as usize]
+    }
+
+    /// Statistics of `channel`, or `None` if no pixel was counted.
+    pub fn stats(&self, channel: Channel) -> Option<ChannelStats> {
+        let bins = self.bins(channel);
+        let min = bins.iter().position(|&count| count > 0)?;
+        let max = bins.iter().rposition(|&count| count > 0)?;
+        let total: u64 = bins
+            .iter()
+            .enumerate()
+            .map(|(value, &count)| value as u64 * count)
+            .sum();
+
+        // The lower median, the first value that at least half of the pixels do not exceed
+        let mut seen = 0;
+        let median = bins
+            .iter()
+            .position(|&count| {
+                seen += count;
+                seen * 2 >= self.pixels
+            })
+            .unwrap_or(max);
+
+        let percent = |count: u64| count as f32 * 100.0 / self.pixels as f32;
+        Some(ChannelStats {
+            min: min as u8,
+            max: max as u8,
+            mean: total as f32 / self.pixels as f32,
+            median: median as u8,
+            clipped_shadows: percent(bins[0]),
+            clipped_highlights: percent(bins[255]),
+        })
+    }
+}
@@ -1,9 +1,37 @@
+pub mod adjust;
+pub mod crop;
+pub mod error;
 #[cfg(feature = "exif")]
 pub mod exif;
+pub mod histogram;
+pub mod operation;
+pub mod orientation;
+pub mod pipeline;
+pub mod pyramid;
+pub mod resize;
 pub mod state;
+pub mod transform;
 use anyhow::Result;
-use image::{DynamicImage, GenericImageView};
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, ImageDecoder, ImageFormat, ImageReader};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub use adjust::Adjustment;
+pub use crop::{crop, crop_image};
+pub use error::HdimError;
+pub use histogram::{Channel, Histogram};
+pub use operation::{Flip, Operation, Rotation, apply_all};
+pub use pipeline::Pipeline;
+pub use pyramid::{Level, Pyramid};
+pub use resize::ResizeMode;
+pub use transform::{Fill, Interpolation};
+
+/// A `generation` no image has had before.
+pub fn new_generation() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
 
 #[derive(Debug, Clone)]
 pub struct HdimImage {
@@ -11,11 +39,39 @@ pub struct HdimImage {
     pub data: DynamicImage,
     pub width: u32,
     pub height: u32,
+    /// The format the image was decoded from, used when saving edits.
+    pub format: Option<ImageFormat>,
+    /// Identifies the pixels in `data`. Clones keep it, loading and `with_data` take a new one.
+    pub generation: u64,
+    /// Smaller copies of `data` for zoomed out views, for the pixels of `generation` only.
+    pub pyramid: Pyramid,
+    /// How the file's EXIF data says the stored pixels have to be turned to be upright.
+    pub orientation: Orientation,
+    /// Whether `orientation` has been applied to `data`.
+    pub oriented: bool,
 }
 
 impl HdimImage {
+    /// Loads an image and turns it upright according to its EXIF orientation.
     pub fn from_path(path: &Path) -> Result<Self> {
-        let data = image::open(path)?;
+        Self::load(path, true)
+    }
+
+    /// Loads an image with its pixels as they are stored, ignoring the EXIF orientation.
+    pub fn from_path_raw(path: &Path) -> Result<Self> {
+        Self::load(path, false)
+    }
+
+    fn load(path: &Path, oriented: bool) -> Result<Self> {
+        let reader = ImageReader::open(path)?.with_guessed_format()?;
+        let format = reader.format();
+        let mut decoder = reader.into_decoder()?;
+        // A broken EXIF block should not keep the image from loading
+        let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+        let mut data = DynamicImage::from_decoder(decoder)?;
+        if oriented {
+            data.apply_orientation(orientation);
+        }
         let (width, height) = data.dimensions();
 
         Ok(HdimImage {
@@ -23,8 +79,67 @@ impl HdimImage {
             data,
             width,
             height,
+            format,
+            generation: new_generation(),
+            pyramid: Pyramid::default(),
+            orientation,
+            oriented,
         })
     }
+
+    /// The image with its EXIF orientation applied, or with the stored pixels when `apply` is false.
+    pub fn with_orientation(&self, apply: bool) -> Self {
+        if apply == self.oriented {
+            return self.clone();
+        }
+        let mut data = self.data.clone();
+        data.apply_orientation(if apply {
+            self.orientation
+        } else {
+            orientation::inverse(self.orientation)
+        });
+        Self {
+            oriented: apply,
+            ..self.with_data(data)
+        }
+    }
+
+    /// Creates a new `HdimImage` with the same path and format but different pixel data.
+    ///
+    /// The pyramid starts out empty, with the same memory budget.
+    pub fn with_data(&self, data: DynamicImage) -> Self {
+        let (width, height) = data.dimensions();
+
+        HdimImage {
+            path: self.path.clone(),
+            data,
+            width,
+            height,
+            format: self.format,
+            generation: new_generation(),
+            pyramid: Pyramid::with_budget(self.pyramid.budget()),
+            orientation: self.orientation,
+            oriented: self.oriented,
+        }
+    }
+
+    /// The coarsest pyramid level of `data` that still has a pixel for every `scale` pixels,
+    /// see `Pyramid::level_for`.
+    pub fn level_for(&self, scale: f32) -> Option<Level> {
+        self.pyramid.level_for(self.generation, &self.data, scale)
+    }
+
+    /// Writes the image to `path` using the format implied by its extension.
+    ///
+    /// Falls back to the format of the source image when the extension is missing or unknown.
+    pub fn save_as(&self, path: &Path) -> Result<(), HdimError> {
+        let format = ImageFormat::from_path(path)
+            .ok()
+            .or(self.format)
+            .ok_or_else(|| HdimError::UnknownFormat(path.to_path_buf()))?;
+        self.data.save_with_format(path, format)?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -50,3 +165,13 @@ pub fn calculate_resize(img: &DynamicImage, max_size: Size) -> Size {
         height: (h as f64 * ratio) as u32,
     }
 }
+
+/// Calculates how many source pixels a viewport of `viewport` terminal cells covers at `zoom`.
+///
+/// `zoom` is `source_pixels / terminal_characters`, and every cell shows two vertical pixels.
+pub fn viewport_source_size(viewport: Size, zoom: f32) -> Size {
+    Size {
+        width: (viewport.width as f32 * zoom).round() as u32,
+        height: (viewport.height as f32 * zoom * 2.0).round() as u32,
+    }
+}
@@ -0,0 +1,167 @@
+use crate::Size;
+use crate::adjust::adjust;
+use crate::crop::crop_image;
+use crate::error::HdimError;
+use crate::resize::{MAX_PIXELS, ResizeMode, resize, resized_pixels};
+use crate::state::{AdjustState, CropState};
+use crate::transform::{Fill, Interpolation, check_rotated_size, rotate_free};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use std::fmt;
+
+/// A clockwise rotation by a multiple of 90 degrees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+/// The axis an image is mirrored along.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flip {
+    Horizontal,
+    Vertical,
+}
+
+/// A single edit that can be applied to an image.
+///
+/// Operations never modify their input, so a list of them can be replayed
+/// onto the original image or onto any other image.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    Crop(CropState),
+    Rotate(Rotation),
+    /// Rotates clockwise by any angle in degrees, see `transform::rotate_free`.
+    RotateFree {
+        degrees: f32,
+        interpolation: Interpolation,
+        fill: Fill,
+    },
+    Flip(Flip),
+    /// Resizes to `width`x`height`, see `resize::ResizeMode` for how the aspect ratio is treated.
+    Resize {
+        width: u32,
+        height: u32,
+        mode: ResizeMode,
+        filter: FilterType,
+    },
+    /// Adds the value to every color channel.
+    Brighten(i32),
+    /// Scales the contrast; positive values increase it, negative values decrease it.
+    Contrast(f32),
+    /// Rotates the hue by the given number of degrees.
+    HueRotate(i32),
+    Grayscale,
+    Invert,
+    /// Tonal adjustments, see `adjust::adjust`.
+    Adjust(AdjustState),
+}
+
+impl Operation {
+    /// Applies the operation to `image`, returning the edited copy.
+    pub fn apply(&self, image: &DynamicImage) -> Result<DynamicImage, HdimError> {
+        Ok(match self {
+            Operation::Crop(state) => crop_image(image, state)?,
+            Operation::Rotate(Rotation::Rotate90) => image.rotate90(),
+            Operation::Rotate(Rotation::Rotate180) => image.rotate180(),
+            Operation::Rotate(Rotation::Rotate270) => image.rotate270(),
+            Operation::RotateFree {
+                degrees,
+                interpolation,
+                fill,
+            } => {
+                if !degrees.is_finite() {
+                    return Err(HdimError::InvalidAngle(*degrees));
+                }
+                check_rotated_size(image.width(), image.height(), *degrees)?;
+                rotate_free(image, *degrees, *interpolation, *fill)
+            }
+            Operation::Flip(Flip::Horizontal) => image.fliph(),
+            Operation::Flip(Flip::Vertical) => image.flipv(),
+            Operation::Resize {
+                width,
+                height,
+                mode,
+                filter,
+            } => {
+                if *width == 0 || *height == 0 {
+                    return Err(HdimError::EmptyResize {
+                        width: *width,
+                        height: *height,
+                    });
+                }
+                let (source_width, source_height) = image.dimensions();
+                let source = Size {
+                    width: source_width,
+                    height: source_height,
+                };
+                if resized_pixels(source, *width, *height, *mode) > MAX_PIXELS {
+                    return Err(HdimError::ResizeTooLarge {
+                        width: *width,
+                        height: *height,
+                    });
+                }
+                resize(image, *width, *height, *mode, *filter)
+            }
+            Operation::Brighten(value) => image.brighten(*value),
+            Operation::Contrast(value) => image.adjust_contrast(*value),
+            Operation::HueRotate(degrees) => image.huerotate(*degrees),
+            Operation::Grayscale => image.grayscale(),
+            Operation::Invert => {
+                let mut inverted = image.clone();
+                inverted.invert();
+                inverted
+            }
+            Operation::Adjust(state) => adjust(image, state),
+        })
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Crop(state) => write!(
+                f,
+                "Crop L{} R{} T{} B{}",
+                state.left, state.right, state.top, state.bottom
+            ),
+            Operation::Rotate(Rotation::Rotate90) => write!(f, "Rotate 90"),
+            Operation::Rotate(Rotation::Rotate180) => write!(f, "Rotate 180"),
+            Operation::Rotate(Rotation::Rotate270) => write!(f, "Rotate 270"),
+            Operation::RotateFree { degrees, .. } => write!(f, "Rotate {}", degrees),
+            Operation::Flip(Flip::Horizontal) => write!(f, "Flip horizontal"),
+            Operation::Flip(Flip::Vertical) => write!(f, "Flip vertical"),
+            Operation::Resize {
+                width,
+                height,
+                mode: ResizeMode::Exact,
+                ..
+            } => write!(f, "Resize {}x{}", width, height),
+            Operation::Resize {
+                width,
+                height,
+                mode,
+                ..
+            } => write!(f, "Resize {}x{} ({})", width, height, mode),
+            Operation::Brighten(value) => write!(f, "Brighten {:+}", value),
+            Operation::Contrast(value) => write!(f, "Contrast {:+.1}", value),
+            Operation::HueRotate(degrees) => write!(f, "Hue {:+}", degrees),
+            Operation::Grayscale => write!(f, "Grayscale"),
+            Operation::Invert => write!(f, "Invert"),
+            Operation::Adjust(state) => write!(f, "Adjust {}", state),
+        }
+    }
+}
+
+/// Applies `operations` in order to `image`.
+pub fn apply_all(
+    image: &DynamicImage,
+    operations: &[Operation],
+) -> Result<DynamicImage, HdimError> {
+    operations
+        .iter()
+        .try_fold(image.clone(), |current, operation| {
+            operation.apply(&current)
+        })
+}
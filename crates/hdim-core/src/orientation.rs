@@ -0,0 +1,27 @@
+use image::metadata::Orientation;
+
+/// The orientation that undoes `orientation`.
+///
+/// Only the quarter turns differ from their inverse, the flips and the
+/// transpositions undo themselves.
+pub fn inverse(orientation: Orientation) -> Orientation {
+    match orientation {
+        Orientation::Rotate90 => Orientation::Rotate270,
+        Orientation::Rotate270 => Orientation::Rotate90,
+        other => other,
+    }
+}
+
+/// A short description of what `orientation` does to the stored pixels, for the status bar.
+pub fn describe(orientation: Orientation) -> &'static str {
+    match orientation {
+        Orientation::NoTransforms => "upright",
+        Orientation::Rotate90 => "rotated 90° clockwise",
+        Orientation::Rotate180 => "rotated 180°",
+        Orientation::Rotate270 => "rotated 90° counterclockwise",
+        Orientation::FlipHorizontal => "mirrored horizontally",
+        Orientation::FlipVertical => "mirrored vertically",
+        Orientation::Rotate90FlipH => "rotated 90° clockwise and mirrored",
+        Orientation::Rotate270FlipH => "rotated 90° counterclockwise and mirrored",
+    }
+}
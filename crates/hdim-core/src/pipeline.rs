@@ -0,0 +1,111 @@
+use crate::HdimImage;
+use crate::error::HdimError;
+use crate::operation::{Operation, apply_all};
+
+/// A non-destructive stack of edits on top of an original image.
+///
+/// The original is never modified. Operations are only applied when the
+/// result is requested through `output`, and undone operations stay
+/// available for `redo` until a new operation is pushed.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    original: HdimImage,
+    operations: Vec<Operation>,
+    /// Number of operations in `operations` that are currently applied.
+    applied: usize,
+    /// The result of applying the first `n` operations, reused by `output`.
+    cache: Option<(usize, HdimImage)>,
+}
+
+impl Pipeline {
+    pub fn new(original: HdimImage) -> Self {
+        Self {
+            original,
+            operations: Vec::new(),
+            applied: 0,
+            cache: None,
+        }
+    }
+
+    /// The unedited source image.
+    pub fn original(&self) -> &HdimImage {
+        &self.original
+    }
+
+    /// Swaps in another version of the original image, keeping the edit history.
+    pub fn replace_original(&mut self, original: HdimImage) {
+        self.original = original;
+        self.cache = None;
+    }
+
+    /// The operations that are currently applied, oldest first.
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations[..self.applied]
+    }
+
+    /// The undone operations that `redo` would re-apply, next first.
+    pub fn undone(&self) -> &[Operation] {
+        &self.operations[self.applied..]
+    }
+
+    /// Adds an operation on top of the applied ones, discarding the redo history.
+    pub fn push(&mut self, operation: Operation) {
+        // A result that includes undone operations does not match the new history
+        if self.cache.as_ref().is_some_and(|(n, _)| *n > self.applied) {
+            self.cache = None;
+        }
+        self.operations.truncate(self.applied);
+        self.operations.push(operation);
+        self.applied += 1;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.applied > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.applied < self.operations.len()
+    }
+
+    /// Reverts the most recent operation, returning it.
+    pub fn undo(&mut self) -> Option<&Operation> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.applied -= 1;
+        self.operations.get(self.applied)
+    }
+
+    /// Re-applies the most recently undone operation, returning it.
+    pub fn redo(&mut self) -> Option<&Operation> {
+        if !self.can_redo() {
+            return None;
+        }
+        self.applied += 1;
+        self.operations.get(self.applied - 1)
+    }
+
+    /// Returns the original with all applied operations, computing it if needed.
+    ///
+    /// Work is resumed from the last computed result when only new operations were added.
+    pub fn output(&mut self) -> Result<&HdimImage, HdimError> {
+        if self.applied == 0 {
+            return Ok(&self.original);
+        }
+
+        let (start, base) = match self.cache.take() {
+            Some((n, image)) if n <= self.applied => (n, image),
+            _ => (0, self.original.clone()),
+        };
+        let data = apply_all(&base.data, &self.operations[start..self.applied])?;
+        let cache = self
+            .cache
+            .insert((self.applied, self.original.with_data(data)));
+        Ok(&cache.1)
+    }
+
+    /// Applies the currently applied operations to another image.
+    pub fn replay(&self, image: &HdimImage) -> Result<HdimImage, HdimError> {
+        Ok(image.with_data(apply_all(&image.data, self.operations())?))
+    }
+}
@@ -0,0 +1,229 @@
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use palette::{LinSrgb, Srgb};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// Memory the cached levels of a pyramid may use unless configured otherwise, in bytes.
+pub const DEFAULT_BUDGET: usize = 256 * 1024 * 1024;
+
+/// Half-resolution copies of an image, generated the first time they are needed.
+///
+/// Level 0 is the image itself and is never stored, level `k` is `2^k` times
+/// smaller along both axes. Levels are averaged in linear light with
+/// premultiplied alpha. When a new level would exceed the memory budget the
+/// least recently used levels are dropped.
+///
+/// Clones share their cached levels. The levels belong to one generation of
+/// pixels, see `HdimImage::generation`, and are dropped when levels of another
+/// generation are asked for.
+#[derive(Clone, Debug)]
+pub struct Pyramid {
+    cache: Arc<Mutex<Cache>>,
+}
+
+#[derive(Debug)]
+struct Cache {
+    budget: usize,
+    /// The generation of the pixels the levels were generated from.
+    generation: Option<u64>,
+    /// Cached levels with their number and the tick they were last used at.
+    levels: Vec<(u32, Arc<DynamicImage>, u64)>,
+    tick: u64,
+}
+
+/// A level of a `Pyramid`.
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub image: Arc<DynamicImage>,
+    /// Pixels of the original per pixel of this level along each axis, `2^k` for level `k`.
+    pub factor: u32,
+}
+
+impl Default for Pyramid {
+    fn default() -> Self {
+        Self::with_budget(DEFAULT_BUDGET)
+    }
+}
+
+impl Pyramid {
+    /// Creates an empty pyramid whose cached levels use at most `budget` bytes.
+    pub fn with_budget(budget: usize) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(Cache {
+                budget,
+                generation: None,
+                levels: Vec::new(),
+                tick: 0,
+            })),
+        }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.lock().budget
+    }
+
+    /// The bytes used by the cached levels.
+    pub fn cached_bytes(&self) -> usize {
+        self.lock().bytes()
+    }
+
+    /// The numbers of the cached levels, in ascending order.
+    pub fn cached_levels(&self) -> Vec<u32> {
+        let mut levels: Vec<u32> = self.lock().levels.iter().map(|entry| entry.0).collect();
+        levels.sort_unstable();
+        levels
+    }
+
+    /// The coarsest level of `original` that still has a pixel for every `scale` original pixels.
+    ///
+    /// `generation` identifies the pixels of `original`. Returns `None` when the
+    /// original itself should be used: `scale` is below 2, or the level does not
+    /// fit into the budget.
+    pub fn level_for(&self, generation: u64, original: &DynamicImage, scale: f32) -> Option<Level> {
+        if scale.is_nan() || scale < 2.0 {
+            return None;
+        }
+        // The last level is a single pixel
+        let (width, height) = original.dimensions();
+        let last = u32::BITS - width.max(height).max(1).leading_zeros() - 1;
+        let number = (scale.log2().floor() as u32).min(last);
+        if number == 0 {
+            return None;
+        }
+        self.level(generation, original, number)
+    }
+
+    /// Level `number` of `original`, generated from the nearest finer level if it is not cached.
+    ///
+    /// Returns `None` for level 0 and for levels that do not fit into the budget.
+    pub fn level(&self, generation: u64, original: &DynamicImage, number: u32) -> Option<Level> {
+        let mut cache = self.lock();
+        if cache.generation != Some(generation) {
+            cache.levels.clear();
+            cache.generation = Some(generation);
+        }
+        if number == 0 || number >= u32::BITS || level_bytes(original, number) > cache.budget {
+            return None;
+        }
+        cache.tick += 1;
+        let tick = cache.tick;
+        let factor = 1 << number;
+        if let Some(entry) = cache.levels.iter_mut().find(|entry| entry.0 == number) {
+            entry.2 = tick;
+            return Some(Level {
+                image: entry.1.clone(),
+                factor,
+            });
+        }
+
+        let finer = cache
+            .levels
+            .iter()
+            .filter(|entry| entry.0 < number)
+            .max_by_key(|entry| entry.0)
+            .map(|entry| (entry.0, entry.1.clone()));
+        let (mut current, mut image) = match finer {
+            Some((finer, image)) => (finer + 1, Arc::new(halve(&image))),
+            None => (1, Arc::new(halve(original))),
+        };
+        cache.insert(current, image.clone(), tick);
+        while current < number {
+            image = Arc::new(halve(&image));
+            current += 1;
+            cache.insert(current, image.clone(), tick);
+        }
+        Some(Level { image, factor })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache> {
+        // The cache is only a copy of the image, a panic while filling it cannot break it
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Cache {
+    fn bytes(&self) -> usize {
+        self.levels
+            .iter()
+            .map(|(_, image, _)| image.as_bytes().len())
+            .sum()
+    }
+
+    /// Caches a level, dropping the least recently used and then the largest levels to make room.
+    fn insert(&mut self, number: u32, image: Arc<DynamicImage>, tick: u64) {
+        let size = image.as_bytes().len();
+        if size > self.budget {
+            return;
+        }
+        while self.bytes() + size > self.budget {
+            let oldest = self
+                .levels
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (number, _, used))| (*used, *number))
+                .map(|(index, _)| index);
+            match oldest {
+                Some(index) => self.levels.swap_remove(index),
+                None => return,
+            };
+        }
+        self.levels.push((number, image, tick));
+    }
+}
+
+/// The size of level `number` of `original` in bytes.
+fn level_bytes(original: &DynamicImage, number: u32) -> usize {
+    let (width, height) = original.dimensions();
+    let factor = 1u64 << number;
+    (width as u64).div_ceil(factor) as usize * (height as u64).div_ceil(factor) as usize * 4
+}
+
+/// Averages every 2x2 block of pixels into one, in linear light with premultiplied alpha.
+///
+/// Odd widths and heights are rounded up, the last pixels then average fewer pixels.
+fn halve(image: &DynamicImage) -> DynamicImage {
+    let converted;
+    let source = match image.as_rgba8() {
+        Some(buffer) => buffer,
+        None => {
+            converted = image.to_rgba8();
+            &converted
+        }
+    };
+    let (width, height) = source.dimensions();
+    let table = linear_table();
+
+    let halved = RgbaImage::from_fn(width.div_ceil(2), height.div_ceil(2), |x, y| {
+        let mut total = [0.0f32; 4];
+        let mut count = 0.0;
+        for source_y in 2 * y..(2 * y + 2).min(height) {
+            for source_x in 2 * x..(2 * x + 2).min(width) {
+                let [r, g, b, a] = source.get_pixel(source_x, source_y).0;
+                let alpha = a as f32 / 255.0;
+                total[0] += table[r as usize] * alpha;
+                total[1] += table[g as usize] * alpha;
+                total[2] += table[b as usize] * alpha;
+                total[3] += alpha;
+                count += 1.0;
+            }
+        }
+        if total[3] <= 0.0 {
+            return Rgba([0, 0, 0, 0]);
+        }
+        let [r, g, b] = encode([0, 1, 2].map(|c| total[c] / total[3]));
+        Rgba([r, g, b, (total[3] / count * 255.0).round() as u8])
+    });
+    DynamicImage::ImageRgba8(halved)
+}
+
+/// sRGB bytes decoded to linear light, from 0.0 to 1.0.
+pub fn linear_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| std::array::from_fn(|i| Srgb::new(i as u8, 0, 0).into_linear::<f32>().red))
+}
+
+/// Encodes linear light as sRGB bytes.
+fn encode([r, g, b]: [f32; 3]) -> [u8; 3] {
+    let [r, g, b] = [r, g, b].map(|c| c.clamp(0.0, 1.0));
+    let srgb: Srgb<u8> = Srgb::from_linear(LinSrgb::new(r, g, b));
+    [srgb.red, srgb.green, srgb.blue]
+}
@@ -0,0 +1,195 @@
+use crate::Size;
+use crate::operation::Operation;
+use crate::state::ResizeState;
+use image::DynamicImage;
+use image::imageops::FilterType;
+use std::fmt;
+
+/// How a resize treats a target size whose aspect ratio differs from the image's.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResizeMode {
+    /// Stretches the image to exactly the target size.
+    #[default]
+    Exact,
+    /// Keeps the aspect ratio and fits the image inside the target size.
+    Fit,
+    /// Keeps the aspect ratio, covers the target size and crops the overflow around the center.
+    Fill,
+}
+
+impl fmt::Display for ResizeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResizeMode::Exact => write!(f, "exact"),
+            ResizeMode::Fit => write!(f, "fit"),
+            ResizeMode::Fill => write!(f, "fill"),
+        }
+    }
+}
+
+/// Pixels a resize or a free rotation may create at most, 512 MiB as RGBA8 like the allocation limit `image` decodes with.
+pub const MAX_PIXELS: u64 = 1 << 27;
+
+/// The filters of `image::imageops::FilterType`, from the fastest to the sharpest.
+pub const FILTERS: [FilterType; 5] = [
+    FilterType::Nearest,
+    FilterType::Triangle,
+    FilterType::CatmullRom,
+    FilterType::Gaussian,
+    FilterType::Lanczos3,
+];
+
+/// A short name for `filter`, `FilterType` has no `Display`.
+pub fn filter_name(filter: FilterType) -> &'static str {
+    match filter {
+        FilterType::Nearest => "nearest",
+        FilterType::Triangle => "triangle",
+        FilterType::CatmullRom => "catmull-rom",
+        FilterType::Gaussian => "gaussian",
+        FilterType::Lanczos3 => "lanczos3",
+    }
+}
+
+/// Resizes `image` to `width`x`height` according to `mode`.
+///
+/// The caller makes sure the target size is not empty and not above `MAX_PIXELS`.
+pub fn resize(
+    image: &DynamicImage,
+    width: u32,
+    height: u32,
+    mode: ResizeMode,
+    filter: FilterType,
+) -> DynamicImage {
+    match mode {
+        ResizeMode::Exact => image.resize_exact(width, height, filter),
+        ResizeMode::Fit => image.resize(width, height, filter),
+        ResizeMode::Fill => image.resize_to_fill(width, height, filter),
+    }
+}
+
+/// The pixels of the largest image resizing `source` creates, with `ResizeMode::Fill`
+/// the one the overflow is cropped from.
+pub fn resized_pixels(source: Size, width: u32, height: u32, mode: ResizeMode) -> u64 {
+    if mode == ResizeMode::Exact || source.width == 0 || source.height == 0 {
+        return width as u64 * height as u64;
+    }
+    let x = width as f64 / source.width as f64;
+    let y = height as f64 / source.height as f64;
+    let ratio = if mode == ResizeMode::Fit {
+        x.min(y)
+    } else {
+        x.max(y)
+    };
+    let length = |length: u32| (length as f64 * ratio).round().max(1.0);
+    (length(source.width) * length(source.height)) as u64
+}
+
+/// The size of `source` scaled by `percent`, at least one pixel in each direction.
+pub fn scaled(source: Size, percent: u32) -> Size {
+    let scale = |length: u32| {
+        ((length as u64 * percent as u64 + 50) / 100).clamp(1, u32::MAX as u64) as u32
+    };
+    Size {
+        width: scale(source.width),
+        height: scale(source.height),
+    }
+}
+
+/// The length that keeps the aspect ratio of `from`x`to` when `from` becomes `length`.
+fn proportional(length: u32, from: u32, to: u32) -> u32 {
+    if from == 0 {
+        return to;
+    }
+    ((length as f64 * to as f64 / from as f64).round() as u32).max(1)
+}
+
+impl ResizeState {
+    /// A resize of `source` to its own size, with the aspect ratio locked.
+    pub fn new(source: Size) -> Self {
+        Self {
+            width: source.width,
+            height: source.height,
+            mode: ResizeMode::Exact,
+            lock_aspect: true,
+            filter: FilterType::Lanczos3,
+        }
+    }
+
+    /// Sets the target width, and the height too if the aspect ratio is locked.
+    pub fn set_width(&mut self, width: u32, source: Size) {
+        self.width = width;
+        if self.lock_aspect {
+            self.height = proportional(width, source.width, source.height);
+        }
+    }
+
+    /// Sets the target height, and the width too if the aspect ratio is locked.
+    pub fn set_height(&mut self, height: u32, source: Size) {
+        self.height = height;
+        if self.lock_aspect {
+            self.width = proportional(height, source.height, source.width);
+        }
+    }
+
+    /// Sets both target lengths to `percent` of `source`.
+    pub fn set_percent(&mut self, percent: u32, source: Size) {
+        let Size { width, height } = scaled(source, percent);
+        self.width = width;
+        self.height = height;
+    }
+
+    /// The target width as a percentage of `source`, for display.
+    pub fn percent(&self, source: Size) -> f32 {
+        if source.width == 0 {
+            return 100.0;
+        }
+        self.width as f32 * 100.0 / source.width as f32
+    }
+
+    /// Switches to the next filter in `FILTERS`.
+    pub fn cycle_filter(&mut self) {
+        let index = FILTERS.iter().position(|&f| f == self.filter).unwrap_or(0);
+        self.filter = FILTERS[(index + 1) % FILTERS.len()];
+    }
+
+    /// Switches to the next resize mode.
+    pub fn cycle_mode(&mut self) {
+        self.mode = match self.mode {
+            ResizeMode::Exact => ResizeMode::Fit,
+            ResizeMode::Fit => ResizeMode::Fill,
+            ResizeMode::Fill => ResizeMode::Exact,
+        };
+    }
+
+    /// The size an image of `source` size ends up with.
+    pub fn output_size(&self, source: Size) -> Size {
+        let target = Size {
+            width: self.width,
+            height: self.height,
+        };
+        if self.mode != ResizeMode::Fit || source.width == 0 || source.height == 0 {
+            return target;
+        }
+        let ratio = (self.width as f64 / source.width as f64)
+            .min(self.height as f64 / source.height as f64);
+        Size {
+            width: ((source.width as f64 * ratio).round() as u32).max(1),
+            height: ((source.height as f64 * ratio).round() as u32).max(1),
+        }
+    }
+
+    /// Whether resizing an image of `source` size stays within `MAX_PIXELS`.
+    pub fn fits_limit(&self, source: Size) -> bool {
+        resized_pixels(source, self.width, self.height, self.mode) <= MAX_PIXELS
+    }
+
+    /// The resize as an operation for the edit history.
+    pub fn operation(&self) -> Operation {
+        Operation::Resize {
+            width: self.width,
+            height: self.height,
+            mode: self.mode,
+            filter: self.filter,
+        }
+    }
+}
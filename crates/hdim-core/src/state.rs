@@ -1,10 +1,17 @@
+use crate::resize::ResizeMode;
+use crate::transform::{Fill, Interpolation};
+use image::imageops::FilterType;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Tool {
     Crop,
     Exif,
+    Transform,
+    Resize,
+    Adjust,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct CropState {
     pub left: u32,
     pub right: u32,
@@ -12,13 +19,54 @@ pub struct CropState {
     pub bottom: u32,
 }
 
-impl Default for CropState {
+/// The free rotation the Transform tool previews until it is applied.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransformState {
+    /// Clockwise, in degrees.
+    pub degrees: f32,
+    pub interpolation: Interpolation,
+    pub fill: Fill,
+}
+
+/// The target of the Resize tool, applied as one `Operation::Resize`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResizeState {
+    pub width: u32,
+    pub height: u32,
+    pub mode: ResizeMode,
+    /// Whether editing one length changes the other to keep the aspect ratio.
+    pub lock_aspect: bool,
+    pub filter: FilterType,
+}
+
+/// The tonal adjustments of the Adjust tool, applied as one `Operation::Adjust`.
+///
+/// Every value is neutral at its default, see `adjust::Adjustment` for the ranges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdjustState {
+    /// Added to the lightness, in percent.
+    pub brightness: f32,
+    /// Spreads the lightness around middle gray, in percent.
+    pub contrast: f32,
+    /// Scales the chroma, in percent.
+    pub saturation: f32,
+    /// Rotates the hue, in degrees.
+    pub hue: f32,
+    /// Brightens the midtones above 1 and darkens them below.
+    pub gamma: f32,
+    /// Scales the linear light, in stops.
+    pub exposure: f32,
+}
+
+impl Default for AdjustState {
     fn default() -> Self {
         Self {
-            left: 0,
-            right: 0,
-            top: 0,
-            bottom: 0,
+            brightness: 0.0,
+            contrast: 0.0,
+            saturation: 0.0,
+            hue: 0.0,
+            gamma: 1.0,
+            exposure: 0.0,
         }
     }
 }
@@ -0,0 +1,200 @@
+use crate::error::HdimError;
+use crate::resize::MAX_PIXELS;
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgba, Rgba32FImage};
+use std::fmt;
+
+/// How pixels between the source pixels are estimated when rotating by a free angle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// The closest source pixel. Keeps hard pixel edges, but lines get jagged.
+    Nearest,
+    /// A weighted average of the four closest pixels.
+    #[default]
+    Bilinear,
+    /// Catmull-Rom over the sixteen closest pixels. The sharpest of the three.
+    Bicubic,
+}
+
+/// What fills the corners a free rotation uncovers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Fill {
+    #[default]
+    Transparent,
+    /// A straight, not premultiplied, RGBA color.
+    Color([u8; 4]),
+}
+
+impl fmt::Display for Interpolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interpolation::Nearest => write!(f, "nearest"),
+            Interpolation::Bilinear => write!(f, "bilinear"),
+            Interpolation::Bicubic => write!(f, "bicubic"),
+        }
+    }
+}
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fill::Transparent => write!(f, "transparent"),
+            Fill::Color([r, g, b, 255]) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            Fill::Color([r, g, b, a]) => write!(f, "#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+        }
+    }
+}
+
+/// The size of the canvas `rotate_free` creates for a `width`x`height` image, as (width, height).
+pub fn rotated_size(width: u32, height: u32, degrees: f32) -> (u32, u32) {
+    let (sin, cos) = (degrees as f64).to_radians().sin_cos();
+    // Trims the float error of angles like 90 degrees, which would add a column
+    let extent = |length: f64| (length - 1e-6).ceil().max(1.0) as u32;
+    (
+        extent(width as f64 * cos.abs() + height as f64 * sin.abs()),
+        extent(width as f64 * sin.abs() + height as f64 * cos.abs()),
+    )
+}
+
+/// Fails with `HdimError::RotationTooLarge` if the canvas of the rotation would be above `MAX_PIXELS`.
+pub fn check_rotated_size(width: u32, height: u32, degrees: f32) -> Result<(), HdimError> {
+    let (width, height) = rotated_size(width, height, degrees);
+    if width as u64 * height as u64 > MAX_PIXELS {
+        return Err(HdimError::RotationTooLarge { width, height });
+    }
+    Ok(())
+}
+
+/// Rotates `image` clockwise by `degrees` around its center.
+///
+/// The canvas grows to the bounding box of the rotated image, and the
+/// uncovered corners are filled with `fill`. Pixels are interpolated with
+/// premultiplied alpha, so the edges blend into the fill. The result has an
+/// alpha channel and keeps the bit depth of `image`.
+///
+/// The caller makes sure the canvas is not too large, see `check_rotated_size`.
+pub fn rotate_free(
+    image: &DynamicImage,
+    degrees: f32,
+    interpolation: Interpolation,
+    fill: Fill,
+) -> DynamicImage {
+    let source = Premultiplied::new(image, fill);
+    let (width, height) = image.dimensions();
+    let (sin, cos) = (degrees as f64).to_radians().sin_cos();
+    let (new_width, new_height) = rotated_size(width, height, degrees);
+
+    let (center_x, center_y) = (width as f64 / 2.0, height as f64 / 2.0);
+    let (new_center_x, new_center_y) = (new_width as f64 / 2.0, new_height as f64 / 2.0);
+    let rotated = Rgba32FImage::from_fn(new_width, new_height, |x, y| {
+        // The inverse rotation maps every target pixel center back onto the source
+        let dx = x as f64 + 0.5 - new_center_x;
+        let dy = y as f64 + 0.5 - new_center_y;
+        let source_x = dx * cos + dy * sin + center_x - 0.5;
+        let source_y = -dx * sin + dy * cos + center_y - 0.5;
+        Rgba(unpremultiply(source.sample(
+            source_x as f32,
+            source_y as f32,
+            interpolation,
+        )))
+    });
+
+    let rotated = DynamicImage::ImageRgba32F(rotated);
+    match image.color().bytes_per_pixel() / image.color().channel_count() {
+        1 => DynamicImage::ImageRgba8(rotated.to_rgba8()),
+        2 => DynamicImage::ImageRgba16(rotated.to_rgba16()),
+        _ => rotated,
+    }
+}
+
+/// The source pixels with premultiplied alpha, and the fill for everything outside.
+struct Premultiplied {
+    pixels: ImageBuffer<Rgba<f32>, Vec<f32>>,
+    fill: [f32; 4],
+}
+
+impl Premultiplied {
+    fn new(image: &DynamicImage, fill: Fill) -> Self {
+        let mut pixels = image.to_rgba32f();
+        for pixel in pixels.pixels_mut() {
+            pixel.0 = premultiply(pixel.0);
+        }
+        let fill = match fill {
+            Fill::Transparent => [0.0; 4],
+            Fill::Color(color) => premultiply(color.map(|c| c as f32 / 255.0)),
+        };
+        Self { pixels, fill }
+    }
+
+    fn get(&self, x: i64, y: i64) -> [f32; 4] {
+        let (width, height) = self.pixels.dimensions();
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            return self.fill;
+        }
+        self.pixels.get_pixel(x as u32, y as u32).0
+    }
+
+    /// The color at a position in pixel coordinates, where pixel `i` is centered on `i`.
+    fn sample(&self, x: f32, y: f32, interpolation: Interpolation) -> [f32; 4] {
+        match interpolation {
+            Interpolation::Nearest => self.get(x.round() as i64, y.round() as i64),
+            Interpolation::Bilinear => self.weighted(x, y, 0..2, |t| 1.0 - t.abs()),
+            Interpolation::Bicubic => {
+                let mut color = self.weighted(x, y, -1..3, catmull_rom);
+                // Catmull-Rom overshoots at edges, keep the colors valid
+                color[3] = color[3].clamp(0.0, 1.0);
+                for c in 0..3 {
+                    color[c] = color[c].clamp(0.0, color[3]);
+                }
+                color
+            }
+        }
+    }
+
+    /// Sums the pixels at `offsets` from the pixel left of and above the position.
+    fn weighted(
+        &self,
+        x: f32,
+        y: f32,
+        offsets: std::ops::Range<i64>,
+        kernel: fn(f32) -> f32,
+    ) -> [f32; 4] {
+        let (left, top) = (x.floor(), y.floor());
+        let mut total = [0.0; 4];
+        for dy in offsets.clone() {
+            let weight_y = kernel(y - (top + dy as f32));
+            if weight_y == 0.0 {
+                continue;
+            }
+            for dx in offsets.clone() {
+                let weight = weight_y * kernel(x - (left + dx as f32));
+                let pixel = self.get(left as i64 + dx, top as i64 + dy);
+                for c in 0..4 {
+                    total[c] += pixel[c] * weight;
+                }
+            }
+        }
+        total
+    }
+}
+
+fn catmull_rom(t: f32) -> f32 {
+    let t = t.abs();
+    if t < 1.0 {
+        1.5 * t.powi(3) - 2.5 * t.powi(2) + 1.0
+    } else if t < 2.0 {
+        -0.5 * t.powi(3) + 2.5 * t.powi(2) - 4.0 * t + 2.0
+    } else {
+        0.0
+    }
+}
+
+fn premultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r * a, g * a, b * a, a]
+}
+
+fn unpremultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+    [r / a, g / a, b / a, a]
+}
@@ -0,0 +1,161 @@
+use hdim_core::adjust::adjust;
+use hdim_core::state::AdjustState;
+use hdim_core::{Adjustment, Operation};
+use image::{DynamicImage, GenericImageView, Rgb, RgbImage, Rgba, RgbaImage};
+
+fn solid(color: [u8; 3]) -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb(color)))
+}
+
+fn adjusted(color: [u8; 3], state: AdjustState) -> [u8; 3] {
+    let pixel = adjust(&solid(color), &state).get_pixel(0, 0).0;
+    [pixel[0], pixel[1], pixel[2]]
+}
+
+fn is_gray([r, g, b]: [u8; 3]) -> bool {
+    r.abs_diff(g) <= 1 && g.abs_diff(b) <= 1
+}
+
+#[test]
+fn test_neutral_adjustments_keep_the_pixels() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 4, |x, y| {
+        Rgba([x as u8 * 60, y as u8 * 60, 77, 200])
+    }));
+
+    assert_eq!(adjust(&image, &AdjustState::default()), image);
+}
+
+#[test]
+fn test_tonal_adjustments_keep_grays_gray() {
+    let gray = [128, 128, 128];
+    let brighter = [
+        AdjustState {
+            exposure: 1.0,
+            ..AdjustState::default()
+        },
+        AdjustState {
+            gamma: 2.0,
+            ..AdjustState::default()
+        },
+        AdjustState {
+            brightness: 20.0,
+            ..AdjustState::default()
+        },
+    ];
+
+    for state in brighter {
+        let result = adjusted(gray, state);
+        assert!(is_gray(result), "{}: {:?}", state, result);
+        assert!(result[0] > 140, "{}: {:?}", state, result);
+    }
+    // A doubled exposure of linear 0.25 is linear 0.5
+    assert_eq!(
+        adjusted(
+            [137, 137, 137],
+            AdjustState {
+                exposure: 1.0,
+                ..AdjustState::default()
+            }
+        ),
+        [188, 188, 188]
+    );
+}
+
+#[test]
+fn test_contrast_spreads_around_middle_gray() {
+    let more = AdjustState {
+        contrast: 50.0,
+        ..AdjustState::default()
+    };
+    let less = AdjustState {
+        contrast: -100.0,
+        ..AdjustState::default()
+    };
+
+    assert!(adjusted([40, 40, 40], more)[0] < 40);
+    assert!(adjusted([220, 220, 220], more)[0] > 220);
+    assert_eq!(
+        adjusted([10, 10, 10], less),
+        adjusted([240, 240, 240], less)
+    );
+}
+
+#[test]
+fn test_saturation_and_hue_change_the_color() {
+    let desaturated = adjusted(
+        [200, 40, 40],
+        AdjustState {
+            saturation: -100.0,
+            ..AdjustState::default()
+        },
+    );
+    let turned = adjusted(
+        [200, 40, 40],
+        AdjustState {
+            hue: 180.0,
+            ..AdjustState::default()
+        },
+    );
+
+    assert!(is_gray(desaturated), "{:?}", desaturated);
+    // Red turns cyan
+    assert!(
+        turned[0] < turned[1] && turned[0] < turned[2],
+        "{:?}",
+        turned
+    );
+}
+
+#[test]
+fn test_adjustments_keep_alpha_and_bit_depth() {
+    let rgba = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([100, 50, 25, 42])));
+    let rgb16 = DynamicImage::ImageRgb16(image::ImageBuffer::from_pixel(
+        2,
+        2,
+        Rgb([1000u16, 2000, 3000]),
+    ));
+    let state = AdjustState {
+        saturation: 30.0,
+        ..AdjustState::default()
+    };
+
+    assert_eq!(adjust(&rgba, &state).get_pixel(1, 1).0[3], 42);
+    assert!(matches!(
+        adjust(&rgb16, &state),
+        DynamicImage::ImageRgb16(_)
+    ));
+}
+
+#[test]
+fn test_nudges_snap_and_clamp() {
+    let mut state = AdjustState::default();
+
+    for _ in 0..3 {
+        state.nudge(Adjustment::Gamma, 1, true);
+    }
+    state.nudge(Adjustment::Gamma, 2, false);
+    assert_eq!(state.gamma, 1.23);
+    state.nudge(Adjustment::Gamma, -2, false);
+    for _ in 0..3 {
+        state.nudge(Adjustment::Gamma, -1, true);
+    }
+    assert!(state.is_neutral());
+
+    state.nudge(Adjustment::Hue, 100, false);
+    assert_eq!(state.hue, 180.0);
+}
+
+#[test]
+fn test_adjust_operation() {
+    let operation = Operation::Adjust(AdjustState {
+        gamma: 1.2,
+        hue: 30.0,
+        ..AdjustState::default()
+    });
+
+    assert_eq!(operation.to_string(), "Adjust Gamma 1.20, Hue +30°");
+    assert_ne!(
+        operation.apply(&solid([200, 40, 40])).unwrap(),
+        solid([200, 40, 40])
+    );
+}
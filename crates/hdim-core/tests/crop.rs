@@ -0,0 +1,232 @@
+use hdim_core::{
+    HdimError, HdimImage, Pyramid, Size, crop, crop_image, new_generation, state::CropState,
+};
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, ImageFormat, Rgba, RgbaImage};
+use std::path::PathBuf;
+
+fn make_numbered_image(w: u32, h: u32) -> DynamicImage {
+    let mut buffer = RgbaImage::new(w, h);
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        *pixel = Rgba([x as u8, y as u8, 0, 255]);
+    }
+    DynamicImage::ImageRgba8(buffer)
+}
+
+#[test]
+fn test_crop_removes_margins() {
+    let image = make_numbered_image(10, 8);
+    let state = CropState {
+        left: 1,
+        right: 2,
+        top: 3,
+        bottom: 1,
+    };
+
+    let cropped = crop_image(&image, &state).unwrap();
+
+    assert_eq!(cropped.dimensions(), (7, 4));
+    // The top-left pixel of the result is the pixel at (left, top) in the source
+    assert_eq!(cropped.get_pixel(0, 0), Rgba([1, 3, 0, 255]));
+    assert_eq!(cropped.get_pixel(6, 3), Rgba([7, 6, 0, 255]));
+}
+
+#[test]
+fn test_crop_default_state_is_identity() {
+    let image = make_numbered_image(4, 4);
+
+    let cropped = crop_image(&image, &CropState::default()).unwrap();
+
+    assert_eq!(cropped.dimensions(), (4, 4));
+    assert_eq!(cropped.as_bytes(), image.as_bytes());
+}
+
+#[test]
+fn test_crop_rejects_empty_result() {
+    let image = make_numbered_image(10, 10);
+
+    let too_wide = CropState {
+        left: 5,
+        right: 5,
+        ..CropState::default()
+    };
+    let too_tall = CropState {
+        top: 11,
+        ..CropState::default()
+    };
+
+    assert!(matches!(
+        crop_image(&image, &too_wide),
+        Err(HdimError::EmptyCrop { .. })
+    ));
+    assert!(matches!(
+        crop_image(&image, &too_tall),
+        Err(HdimError::EmptyCrop { .. })
+    ));
+}
+
+#[test]
+fn test_crop_keeps_source_metadata() {
+    let image = HdimImage {
+        path: PathBuf::from("photo.png"),
+        data: make_numbered_image(6, 6),
+        width: 6,
+        height: 6,
+        format: Some(ImageFormat::Png),
+        generation: new_generation(),
+        pyramid: Pyramid::default(),
+        orientation: Orientation::NoTransforms,
+        oriented: true,
+    };
+    let state = CropState {
+        left: 1,
+        right: 1,
+        top: 2,
+        bottom: 0,
+    };
+
+    let cropped = crop(&image, &state).unwrap();
+
+    assert_eq!((cropped.width, cropped.height), (4, 4));
+    assert_eq!(cropped.path, image.path);
+    assert_eq!(cropped.format, Some(ImageFormat::Png));
+}
+
+fn make_png(w: u32, h: u32) -> HdimImage {
+    HdimImage {
+        path: PathBuf::from("photo.png"),
+        data: make_numbered_image(w, h),
+        width: w,
+        height: h,
+        format: Some(ImageFormat::Png),
+        generation: new_generation(),
+        pyramid: Pyramid::default(),
+        orientation: Orientation::NoTransforms,
+        oriented: true,
+    }
+}
+
+fn save_and_reload(image: &HdimImage, extension: &str) -> HdimImage {
+    let mut out = std::env::temp_dir();
+    out.push(format!("hdim_save_as_{}.{}", std::process::id(), extension));
+    image.save_as(&out).unwrap();
+    let reloaded = HdimImage::from_path(&out).unwrap();
+    std::fs::remove_file(&out).unwrap();
+    reloaded
+}
+
+#[test]
+fn test_save_as_falls_back_to_source_format() {
+    let reloaded = save_and_reload(&make_png(3, 2), "out");
+
+    assert_eq!(reloaded.format, Some(ImageFormat::Png));
+    assert_eq!((reloaded.width, reloaded.height), (3, 2));
+}
+
+#[test]
+fn test_save_as_prefers_the_extension() {
+    let reloaded = save_and_reload(&make_png(3, 2), "bmp");
+
+    assert_eq!(reloaded.format, Some(ImageFormat::Bmp));
+    assert_eq!((reloaded.width, reloaded.height), (3, 2));
+}
+
+const IMAGE: Size = Size {
+    width: 400,
+    height: 300,
+};
+const VIEWPORT: Size = Size {
+    width: 50,
+    height: 20,
+};
+
+#[test]
+fn test_from_viewport_at_native_zoom() {
+    // One pixel per column, two pixels per row: 50x40 pixels visible
+    let state = CropState::from_viewport(IMAGE, (100, 60), 1.0, VIEWPORT);
+
+    assert_eq!(
+        state,
+        CropState {
+            left: 100,
+            right: 250,
+            top: 60,
+            bottom: 200,
+        }
+    );
+}
+
+#[test]
+fn test_from_viewport_zoomed_in() {
+    // Half a pixel per column: 25x20 pixels visible
+    let state = CropState::from_viewport(IMAGE, (10, 20), 0.5, VIEWPORT);
+
+    assert_eq!(
+        state,
+        CropState {
+            left: 10,
+            right: 365,
+            top: 20,
+            bottom: 260,
+        }
+    );
+}
+
+#[test]
+fn test_from_viewport_zoomed_out() {
+    // Four pixels per column: 200x160 pixels visible
+    let state = CropState::from_viewport(IMAGE, (150, 100), 4.0, VIEWPORT);
+
+    assert_eq!(
+        state,
+        CropState {
+            left: 150,
+            right: 50,
+            top: 100,
+            bottom: 40,
+        }
+    );
+}
+
+#[test]
+fn test_from_viewport_fractional_zoom() {
+    // 50 * 1.5 = 75 pixels wide, 20 * 1.5 * 2 = 60 pixels tall
+    let state = CropState::from_viewport(IMAGE, (0, 0), 1.5, VIEWPORT);
+
+    assert_eq!(
+        state,
+        CropState {
+            left: 0,
+            right: 325,
+            top: 0,
+            bottom: 240,
+        }
+    );
+}
+
+#[test]
+fn test_from_viewport_larger_than_image() {
+    // Eight pixels per column covers more than the whole image
+    let state = CropState::from_viewport(IMAGE, (0, 0), 8.0, VIEWPORT);
+
+    assert_eq!(state, CropState::default());
+}
+
+#[test]
+fn test_from_viewport_result_crops_to_visible_area() {
+    let image = make_numbered_image(40, 30);
+    let size = Size {
+        width: 40,
+        height: 30,
+    };
+    let viewport = Size {
+        width: 10,
+        height: 5,
+    };
+
+    let state = CropState::from_viewport(size, (5, 7), 2.0, viewport);
+    let cropped = crop_image(&image, &state).unwrap();
+
+    assert_eq!(cropped.dimensions(), (20, 20));
+    assert_eq!(cropped.get_pixel(0, 0), Rgba([5, 7, 0, 255]));
+}
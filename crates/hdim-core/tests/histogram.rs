@@ -0,0 +1,99 @@
+use hdim_core::state::CropState;
+use hdim_core::{Channel, HdimError, Histogram};
+use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+
+#[test]
+fn test_stats_of_a_gradient() {
+    // Every gray value once
+    let image = DynamicImage::ImageLuma8(GrayImage::from_fn(256, 1, |x, _| Luma([x as u8])));
+
+    let histogram = Histogram::new(&image);
+
+    assert_eq!(histogram.pixels, 256);
+    for channel in Channel::ALL {
+        let stats = histogram.stats(channel).unwrap();
+        assert_eq!((stats.min, stats.max), (0, 255), "{}", channel);
+        assert_eq!(stats.mean, 127.5);
+        assert_eq!(stats.median, 127);
+        assert_eq!(stats.clipped_shadows, 100.0 / 256.0);
+        assert_eq!(stats.clipped_highlights, 100.0 / 256.0);
+        assert!(histogram.bins(channel).iter().all(|&count| count == 1));
+    }
+}
+
+#[test]
+fn test_channels_are_counted_separately() {
+    // A quarter pure red, the rest black
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(4, 4, |x, y| {
+        if x < 2 && y < 2 {
+            Rgb([255, 0, 0])
+        } else {
+            Rgb([0, 0, 0])
+        }
+    }));
+
+    let histogram = Histogram::new(&image);
+    let red = histogram.stats(Channel::Red).unwrap();
+    let green = histogram.stats(Channel::Green).unwrap();
+    let luminance = histogram.stats(Channel::Luminance).unwrap();
+
+    assert_eq!(red.clipped_highlights, 25.0);
+    assert_eq!(red.clipped_shadows, 75.0);
+    assert_eq!(red.median, 0);
+    assert_eq!((green.max, green.clipped_shadows), (0, 100.0));
+    // Rec. 709 weighs red by 0.2126
+    assert_eq!(luminance.max, 54);
+    assert_eq!(histogram.bins(Channel::Luminance)[54], 4);
+}
+
+#[test]
+fn test_transparent_pixels_are_not_counted() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(2, 1, |x, _| {
+        if x == 0 {
+            Rgba([10, 20, 30, 0])
+        } else {
+            Rgba([200, 200, 200, 128])
+        }
+    }));
+    let transparent = DynamicImage::ImageRgba8(RgbaImage::new(3, 3));
+
+    let histogram = Histogram::new(&image);
+
+    assert_eq!(histogram.pixels, 1);
+    assert_eq!(histogram.stats(Channel::Red).unwrap().min, 200);
+    assert_eq!(Histogram::new(&transparent).stats(Channel::Red), None);
+}
+
+#[test]
+fn test_crops_only_count_the_kept_pixels() {
+    // The left column is black, everything else white
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(4, 3, |x, _| {
+        if x == 0 {
+            Rgb([0, 0, 0])
+        } else {
+            Rgb([255, 255, 255])
+        }
+    }));
+    let without_left = CropState {
+        left: 1,
+        ..CropState::default()
+    };
+
+    let whole = Histogram::of_crop(&image, &CropState::default()).unwrap();
+    let cropped = Histogram::of_crop(&image, &without_left).unwrap();
+
+    assert_eq!(whole, Histogram::new(&image));
+    assert_eq!(cropped.pixels, 9);
+    assert_eq!(cropped.stats(Channel::Luminance).unwrap().min, 255);
+    assert!(matches!(
+        Histogram::of_crop(
+            &image,
+            &CropState {
+                left: 2,
+                right: 2,
+                ..CropState::default()
+            }
+        ),
+        Err(HdimError::EmptyCrop { .. })
+    ));
+}
@@ -0,0 +1,122 @@
+use hdim_core::{HdimImage, Operation, Pipeline, Rotation, orientation};
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use std::path::PathBuf;
+
+/// A fixture whose EXIF orientation is `tag`.
+///
+/// Every fixture shows the same 48x32 image once turned upright: red, green,
+/// blue and white quadrants from the top left, row by row.
+fn fixture(tag: u8) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/images/orientation/{}.jpg", tag))
+}
+
+/// The color at the center of each quadrant, rounded to the nearest primary.
+fn quadrants(image: &DynamicImage) -> [[u8; 3]; 4] {
+    let (width, height) = image.dimensions();
+    [(1, 1), (3, 1), (1, 3), (3, 3)].map(|(x, y)| {
+        let pixel = image.get_pixel(width * x / 4, height * y / 4).0;
+        [0, 1, 2].map(|c| if pixel[c] > 127 { 255 } else { 0 })
+    })
+}
+
+const UPRIGHT: [[u8; 3]; 4] = [[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 255, 255]];
+
+#[test]
+fn test_every_orientation_loads_upright() {
+    for tag in 1..=8 {
+        let image = HdimImage::from_path(&fixture(tag)).unwrap();
+
+        assert_eq!(image.orientation, Orientation::from_exif(tag).unwrap());
+        assert!(image.oriented);
+        assert_eq!((image.width, image.height), (48, 32), "tag {}", tag);
+        assert_eq!(quadrants(&image.data), UPRIGHT, "tag {}", tag);
+    }
+}
+
+#[test]
+fn test_raw_loads_keep_the_stored_pixels() {
+    // Tags 5 to 8 turn the image by a quarter, so the stored pixels are portrait
+    for tag in 1..=8 {
+        let image = HdimImage::from_path_raw(&fixture(tag)).unwrap();
+        let stored = if tag >= 5 { (32, 48) } else { (48, 32) };
+
+        assert!(!image.oriented);
+        assert_eq!((image.width, image.height), stored, "tag {}", tag);
+        assert_eq!(image.data.dimensions(), stored);
+    }
+
+    // Tag 3 is upside down
+    let image = HdimImage::from_path_raw(&fixture(3)).unwrap();
+    let [red, green, blue, white] = UPRIGHT;
+    assert_eq!(quadrants(&image.data), [white, blue, green, red]);
+}
+
+#[test]
+fn test_toggling_the_orientation_round_trips() {
+    for tag in 1..=8 {
+        let oriented = HdimImage::from_path(&fixture(tag)).unwrap();
+        let raw = oriented.with_orientation(false);
+        let raw_loaded = HdimImage::from_path_raw(&fixture(tag)).unwrap();
+
+        assert!(!raw.oriented);
+        assert_eq!(raw.data, raw_loaded.data, "tag {}", tag);
+        assert_eq!(
+            raw.with_orientation(true).data,
+            oriented.data,
+            "tag {}",
+            tag
+        );
+    }
+}
+
+#[test]
+fn test_images_without_exif_are_upright() {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../hdim-render/tests/images/WindowsXP.png");
+    let image = HdimImage::from_path(&path).unwrap();
+
+    assert_eq!(image.orientation, Orientation::NoTransforms);
+    assert_eq!(image.with_orientation(false).data, image.data);
+}
+
+#[test]
+fn test_inverse_undoes_every_orientation() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(3, 2, |x, y| {
+        Rgba([x as u8, y as u8, 0, 255])
+    }));
+    for tag in 1..=8 {
+        let orientation = Orientation::from_exif(tag).unwrap();
+        let mut turned = image.clone();
+        turned.apply_orientation(orientation);
+        turned.apply_orientation(orientation::inverse(orientation));
+
+        assert_eq!(turned, image, "tag {}", tag);
+    }
+}
+
+#[test]
+fn test_edits_apply_to_the_replaced_original() {
+    let oriented = HdimImage::from_path(&fixture(6)).unwrap();
+    let mut pipeline = Pipeline::new(oriented.clone());
+    pipeline.push(Operation::Rotate(Rotation::Rotate90));
+    assert_eq!(pipeline.output().unwrap().data.dimensions(), (32, 48));
+
+    pipeline.replace_original(oriented.with_orientation(false));
+
+    assert_eq!(pipeline.operations().len(), 1);
+    assert_eq!(pipeline.output().unwrap().data.dimensions(), (48, 32));
+}
+
+#[cfg(feature = "exif")]
+#[test]
+fn test_exif_data_reads_the_same_tag() {
+    use hdim_core::exif::ExifData;
+
+    for tag in 1..=8 {
+        let file = std::fs::File::open(fixture(tag)).unwrap();
+        let exif = ExifData::get_exif_data(file).unwrap();
+
+        assert_eq!(exif.orientation, Some(tag as u16));
+    }
+}
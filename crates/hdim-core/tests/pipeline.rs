@@ -0,0 +1,186 @@
+use hdim_core::{
+    Flip, HdimImage, Operation, Pipeline, Pyramid, ResizeMode, Rotation, apply_all, new_generation,
+    state::CropState,
+};
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage, imageops::FilterType};
+use std::path::PathBuf;
+
+fn make_image(w: u32, h: u32) -> HdimImage {
+    let mut buffer = RgbaImage::new(w, h);
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        *pixel = Rgba([x as u8, y as u8, 0, 255]);
+    }
+    HdimImage {
+        path: PathBuf::from("test.png"),
+        data: DynamicImage::ImageRgba8(buffer),
+        width: w,
+        height: h,
+        format: None,
+        generation: new_generation(),
+        pyramid: Pyramid::default(),
+        orientation: Orientation::NoTransforms,
+        oriented: true,
+    }
+}
+
+#[test]
+fn test_operations_are_applied_in_order() {
+    let image = make_image(4, 2);
+    let operations = [
+        Operation::Crop(CropState {
+            left: 1,
+            ..CropState::default()
+        }),
+        Operation::Rotate(Rotation::Rotate90),
+    ];
+
+    let result = apply_all(&image.data, &operations).unwrap();
+
+    // Cropping 4x2 to 3x2 then rotating gives 2x3
+    assert_eq!(result.dimensions(), (2, 3));
+    // The bottom-left source pixel of the crop ends up top-left after a clockwise turn
+    assert_eq!(result.get_pixel(0, 0), Rgba([1, 1, 0, 255]));
+}
+
+#[test]
+fn test_flip_and_resize() {
+    let image = make_image(3, 2);
+
+    let flipped = Operation::Flip(Flip::Horizontal)
+        .apply(&image.data)
+        .unwrap();
+    let resized = Operation::Resize {
+        width: 6,
+        height: 4,
+        mode: ResizeMode::Exact,
+        filter: FilterType::Nearest,
+    }
+    .apply(&image.data)
+    .unwrap();
+
+    assert_eq!(flipped.get_pixel(0, 0), Rgba([2, 0, 0, 255]));
+    assert_eq!(resized.dimensions(), (6, 4));
+    assert!(
+        Operation::Resize {
+            width: 0,
+            height: 4,
+            mode: ResizeMode::Exact,
+            filter: FilterType::Nearest,
+        }
+        .apply(&image.data)
+        .is_err()
+    );
+}
+
+#[test]
+fn test_output_is_lazy_and_keeps_original() {
+    let mut pipeline = Pipeline::new(make_image(4, 4));
+    pipeline.push(Operation::Rotate(Rotation::Rotate90));
+    pipeline.push(Operation::Crop(CropState {
+        top: 1,
+        ..CropState::default()
+    }));
+
+    let output = pipeline.output().unwrap();
+
+    assert_eq!((output.width, output.height), (4, 3));
+    assert_eq!(
+        (pipeline.original().width, pipeline.original().height),
+        (4, 4)
+    );
+}
+
+#[test]
+fn test_undo_and_redo() {
+    let mut pipeline = Pipeline::new(make_image(4, 2));
+    pipeline.push(Operation::Rotate(Rotation::Rotate90));
+    pipeline.push(Operation::Invert);
+    pipeline.output().unwrap();
+
+    assert_eq!(pipeline.undo(), Some(&Operation::Invert));
+    assert_eq!(
+        pipeline.undo(),
+        Some(&Operation::Rotate(Rotation::Rotate90))
+    );
+    assert_eq!(pipeline.undo(), None);
+    let original = pipeline.original().data.clone();
+    assert_eq!(pipeline.output().unwrap().data, original);
+
+    assert_eq!(
+        pipeline.redo(),
+        Some(&Operation::Rotate(Rotation::Rotate90))
+    );
+    assert_eq!(
+        pipeline.operations(),
+        &[Operation::Rotate(Rotation::Rotate90)]
+    );
+    assert_eq!(pipeline.undone(), &[Operation::Invert]);
+    assert_eq!(pipeline.output().unwrap().width, 2);
+}
+
+#[test]
+fn test_push_discards_redo_history() {
+    let mut pipeline = Pipeline::new(make_image(4, 2));
+    pipeline.push(Operation::Grayscale);
+    pipeline.undo();
+
+    pipeline.push(Operation::Flip(Flip::Vertical));
+
+    assert!(!pipeline.can_redo());
+    assert_eq!(pipeline.operations(), &[Operation::Flip(Flip::Vertical)]);
+}
+
+#[test]
+fn test_output_after_undo_matches_fresh_pipeline() {
+    let mut pipeline = Pipeline::new(make_image(5, 3));
+    pipeline.push(Operation::Rotate(Rotation::Rotate180));
+    pipeline.push(Operation::Brighten(20));
+    pipeline.output().unwrap();
+    pipeline.undo();
+
+    let mut fresh = Pipeline::new(make_image(5, 3));
+    fresh.push(Operation::Rotate(Rotation::Rotate180));
+
+    assert_eq!(
+        pipeline.output().unwrap().data,
+        fresh.output().unwrap().data
+    );
+}
+
+#[test]
+fn test_push_after_undo_ignores_the_cached_output() {
+    let mut pipeline = Pipeline::new(make_image(4, 2));
+    pipeline.push(Operation::Flip(Flip::Horizontal));
+    pipeline.push(Operation::Rotate(Rotation::Rotate90));
+    assert_eq!(pipeline.output().unwrap().data.dimensions(), (2, 4));
+    pipeline.undo();
+
+    pipeline.push(Operation::Flip(Flip::Vertical));
+
+    let mut fresh = Pipeline::new(make_image(4, 2));
+    fresh.push(Operation::Flip(Flip::Horizontal));
+    fresh.push(Operation::Flip(Flip::Vertical));
+    assert_eq!(pipeline.output().unwrap().data.dimensions(), (4, 2));
+    assert_eq!(
+        pipeline.output().unwrap().data,
+        fresh.output().unwrap().data
+    );
+}
+
+#[test]
+fn test_replay_onto_another_image() {
+    let mut pipeline = Pipeline::new(make_image(4, 4));
+    pipeline.push(Operation::Crop(CropState {
+        left: 1,
+        right: 1,
+        ..CropState::default()
+    }));
+    pipeline.push(Operation::Rotate(Rotation::Rotate270));
+
+    let other = make_image(10, 6);
+    let replayed = pipeline.replay(&other).unwrap();
+
+    assert_eq!((replayed.width, replayed.height), (6, 8));
+    assert_eq!(replayed.path, other.path);
+}
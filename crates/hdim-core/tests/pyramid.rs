@@ -0,0 +1,141 @@
+use hdim_core::{HdimImage, Pyramid, new_generation};
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use std::path::PathBuf;
+
+fn gradient(w: u32, h: u32) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(w, h, |x, y| {
+        Rgba([(x % 256) as u8, (y % 256) as u8, 128, 255])
+    }))
+}
+
+#[test]
+fn test_levels_are_generated_lazily() {
+    let image = gradient(100, 61);
+    let pyramid = Pyramid::default();
+    assert!(pyramid.cached_levels().is_empty());
+
+    let level = pyramid.level(0, &image, 2).unwrap();
+
+    assert_eq!(level.factor, 4);
+    assert_eq!(level.image.dimensions(), (25, 16));
+    // The level in between was needed to build it
+    assert_eq!(pyramid.cached_levels(), vec![1, 2]);
+    assert_eq!(
+        pyramid.level(0, &image, 1).unwrap().image.dimensions(),
+        (50, 31)
+    );
+}
+
+#[test]
+fn test_level_for_picks_the_nearest_finer_level() {
+    let image = gradient(64, 64);
+    let pyramid = Pyramid::default();
+    let factor = |scale| {
+        pyramid
+            .level_for(0, &image, scale)
+            .map(|level| level.factor)
+    };
+
+    assert_eq!(factor(1.0), None);
+    assert_eq!(factor(1.9), None);
+    assert_eq!(factor(2.0), Some(2));
+    assert_eq!(factor(3.9), Some(2));
+    assert_eq!(factor(5.0), Some(4));
+    // Never smaller than a single pixel
+    assert_eq!(factor(1000.0), Some(64));
+}
+
+#[test]
+fn test_levels_average_in_linear_light() {
+    let checkerboard = DynamicImage::ImageRgba8(RgbaImage::from_fn(8, 8, |x, y| {
+        if (x + y) % 2 == 0 {
+            Rgba([0, 0, 0, 255])
+        } else {
+            Rgba([255, 255, 255, 255])
+        }
+    }));
+    let level = Pyramid::default().level(0, &checkerboard, 1).unwrap();
+
+    let image = level.image.as_rgba8().unwrap();
+    assert!(image.pixels().all(|p| p.0 == [188, 188, 188, 255]));
+}
+
+#[test]
+fn test_transparent_pixels_do_not_bleed() {
+    let mut buffer = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 0]));
+    buffer.put_pixel(0, 0, Rgba([0, 0, 255, 255]));
+    let level = Pyramid::default()
+        .level(0, &DynamicImage::ImageRgba8(buffer), 1)
+        .unwrap();
+
+    let pixel = level.image.get_pixel(0, 0);
+    assert_eq!(pixel.0, [0, 0, 255, 64]);
+}
+
+#[test]
+fn test_budget_bounds_the_cached_levels() {
+    let image = gradient(64, 64);
+    // Level 1 takes 32 * 32 * 4 = 4096 bytes, level 2 1024 and level 3 256
+    let pyramid = Pyramid::with_budget(4096);
+
+    pyramid.level(0, &image, 1).unwrap();
+    assert_eq!(pyramid.cached_levels(), vec![1]);
+
+    pyramid.level(0, &image, 3).unwrap();
+    assert!(pyramid.cached_bytes() <= 4096);
+    assert_eq!(pyramid.cached_levels(), vec![2, 3]);
+
+    // Levels larger than the whole budget are never built
+    assert!(Pyramid::with_budget(4095).level(0, &image, 1).is_none());
+}
+
+#[test]
+fn test_clones_share_levels() {
+    let image = gradient(16, 16);
+    let pyramid = Pyramid::default();
+    let clone = pyramid.clone();
+
+    clone.level(0, &image, 1).unwrap();
+
+    assert_eq!(pyramid.cached_levels(), vec![1]);
+}
+
+#[test]
+fn test_levels_of_another_generation_are_not_reused() {
+    let pyramid = Pyramid::default();
+    let image = gradient(16, 16);
+    pyramid.level(0, &image, 1).unwrap();
+    let black = DynamicImage::ImageRgba8(RgbaImage::from_pixel(16, 16, Rgba([0, 0, 0, 255])));
+
+    let level = pyramid.level(1, &black, 1).unwrap();
+
+    assert!(level.image.pixels().all(|(_, _, p)| p.0 == [0, 0, 0, 255]));
+    assert_eq!(pyramid.cached_levels(), vec![1]);
+}
+
+#[test]
+fn test_image_clones_keep_their_generation() {
+    let image = HdimImage {
+        path: PathBuf::from("gradient.png"),
+        data: gradient(16, 16),
+        width: 16,
+        height: 16,
+        format: None,
+        generation: new_generation(),
+        pyramid: Pyramid::default(),
+        orientation: Orientation::NoTransforms,
+        oriented: true,
+    };
+    let clone = image.clone();
+    let edited = image.with_data(gradient(16, 16));
+
+    // Clones reading in turn keep each other's levels
+    let level = image.level_for(2.0).unwrap();
+    assert!(std::sync::Arc::ptr_eq(
+        &level.image,
+        &clone.level_for(2.0).unwrap().image
+    ));
+    assert_eq!(clone.generation, image.generation);
+    assert_ne!(edited.generation, image.generation);
+}
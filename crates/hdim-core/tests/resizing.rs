@@ -1,5 +1,8 @@
-use hdim_core::{Size, calculate_resize};
-use image::{DynamicImage, RgbaImage};
+use hdim_core::resize::{FILTERS, MAX_PIXELS, filter_name, resized_pixels, scaled};
+use hdim_core::state::ResizeState;
+use hdim_core::{HdimError, Operation, ResizeMode, Size, calculate_resize};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
 
 fn make_dummy_image(w: u32, h: u32) -> DynamicImage {
     DynamicImage::ImageRgba8(RgbaImage::new(w, h))
@@ -19,3 +22,137 @@ fn test_resize_logic_external() {
     // Remember our logic doubles the height budget for terminal cells
     assert!(result.height <= 100);
 }
+
+const SOURCE: Size = Size {
+    width: 400,
+    height: 300,
+};
+
+#[test]
+fn test_modes_treat_the_aspect_ratio_differently() {
+    let img = make_dummy_image(400, 300);
+
+    for (mode, expected) in [
+        (ResizeMode::Exact, (100, 100)),
+        (ResizeMode::Fit, (100, 75)),
+        (ResizeMode::Fill, (100, 100)),
+    ] {
+        let state = ResizeState {
+            width: 100,
+            height: 100,
+            mode,
+            lock_aspect: false,
+            filter: FilterType::Triangle,
+        };
+        let resized = state.operation().apply(&img).unwrap();
+
+        assert_eq!(resized.dimensions(), expected, "{}", mode);
+        let size = state.output_size(SOURCE);
+        assert_eq!((size.width, size.height), expected, "{}", mode);
+    }
+}
+
+#[test]
+fn test_fill_crops_around_the_center() {
+    // Left third red, middle third green, right third blue
+    let img = DynamicImage::ImageRgba8(RgbaImage::from_fn(30, 10, |x, _| match x / 10 {
+        0 => Rgba([255, 0, 0, 255]),
+        1 => Rgba([0, 255, 0, 255]),
+        _ => Rgba([0, 0, 255, 255]),
+    }));
+    let fill = Operation::Resize {
+        width: 10,
+        height: 10,
+        mode: ResizeMode::Fill,
+        filter: FilterType::Nearest,
+    };
+
+    let resized = fill.apply(&img).unwrap();
+
+    assert_eq!(resized.dimensions(), (10, 10));
+    assert_eq!(resized.get_pixel(0, 0), Rgba([0, 255, 0, 255]));
+    assert_eq!(resized.get_pixel(9, 9), Rgba([0, 255, 0, 255]));
+    assert_eq!(fill.to_string(), "Resize 10x10 (fill)");
+}
+
+#[test]
+fn test_locked_aspect_follows_the_edited_length() {
+    let mut state = ResizeState::new(SOURCE);
+
+    state.set_width(200, SOURCE);
+    assert_eq!((state.width, state.height), (200, 150));
+    state.set_height(30, SOURCE);
+    assert_eq!((state.width, state.height), (40, 30));
+
+    state.lock_aspect = false;
+    state.set_width(100, SOURCE);
+    assert_eq!((state.width, state.height), (100, 30));
+}
+
+#[test]
+fn test_percentages_scale_both_lengths() {
+    let mut state = ResizeState::new(SOURCE);
+
+    state.set_percent(25, SOURCE);
+    assert_eq!((state.width, state.height), (100, 75));
+    assert_eq!(state.percent(SOURCE), 25.0);
+
+    // Tiny images keep at least one pixel
+    let tiny = scaled(
+        Size {
+            width: 3,
+            height: 1,
+        },
+        10,
+    );
+    assert_eq!((tiny.width, tiny.height), (1, 1));
+}
+
+#[test]
+fn test_filters_cycle_through_every_filter() {
+    let mut state = ResizeState::new(SOURCE);
+    assert_eq!(state.filter, FilterType::Lanczos3);
+
+    let mut names = Vec::new();
+    for _ in FILTERS {
+        state.cycle_filter();
+        names.push(filter_name(state.filter));
+    }
+
+    assert_eq!(
+        names,
+        ["nearest", "triangle", "catmull-rom", "gaussian", "lanczos3"]
+    );
+}
+
+#[test]
+fn test_resizes_above_the_pixel_limit_are_rejected() {
+    let huge = Operation::Resize {
+        width: 100_000,
+        height: 100_000,
+        mode: ResizeMode::Exact,
+        filter: FilterType::Nearest,
+    };
+    assert!(matches!(
+        huge.apply(&make_dummy_image(4, 4)),
+        Err(HdimError::ResizeTooLarge { .. })
+    ));
+
+    // Filling a square with a wide image scales it far beyond the target before cropping
+    let wide = Size {
+        width: 10_000,
+        height: 10,
+    };
+    assert!(resized_pixels(wide, 4000, 4000, ResizeMode::Fill) > MAX_PIXELS);
+    assert_eq!(resized_pixels(wide, 4000, 4000, ResizeMode::Fit), 4000 * 4);
+    let mut state = ResizeState::new(wide);
+    state.lock_aspect = false;
+    state.set_width(4000, wide);
+    state.set_height(4000, wide);
+    assert!(state.fits_limit(wide));
+    state.mode = ResizeMode::Fill;
+    assert!(!state.fits_limit(wide));
+
+    // Huge percentages saturate instead of wrapping around
+    assert_eq!(scaled(SOURCE, u32::MAX).width, u32::MAX);
+}
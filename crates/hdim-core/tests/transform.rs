@@ -0,0 +1,116 @@
+use hdim_core::transform::{rotate_free, rotated_size};
+use hdim_core::{Fill, HdimError, Interpolation, Operation};
+use image::{DynamicImage, GenericImageView, GrayImage, Rgb, RgbImage, Rgba, RgbaImage};
+
+fn numbered(w: u32, h: u32) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(w, h, |x, y| {
+        Rgba([x as u8 * 10, y as u8 * 10, 100, 255])
+    }))
+}
+
+#[test]
+fn test_quarter_turns_match_the_exact_rotations() {
+    let image = numbered(6, 4);
+    for interpolation in [
+        Interpolation::Nearest,
+        Interpolation::Bilinear,
+        Interpolation::Bicubic,
+    ] {
+        let rotated = rotate_free(&image, 90.0, interpolation, Fill::Transparent);
+        assert_eq!(rotated, image.rotate90(), "{}", interpolation);
+
+        let rotated = rotate_free(&image, -180.0, interpolation, Fill::Transparent);
+        assert_eq!(rotated, image.rotate180(), "{}", interpolation);
+    }
+    assert_eq!(
+        rotate_free(&image, 0.0, Interpolation::Bicubic, Fill::Transparent),
+        image
+    );
+}
+
+#[test]
+fn test_free_angles_grow_the_canvas_and_fill_the_corners() {
+    let image = numbered(20, 10);
+
+    let transparent = rotate_free(&image, 45.0, Interpolation::Bilinear, Fill::Transparent);
+    let white = rotate_free(
+        &image,
+        45.0,
+        Interpolation::Bilinear,
+        Fill::Color([255, 255, 255, 255]),
+    );
+
+    // (20 + 10) / sqrt(2) = 21.2
+    assert_eq!(transparent.dimensions(), (22, 22));
+    assert_eq!(transparent.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+    assert_eq!(white.get_pixel(0, 0), Rgba([255, 255, 255, 255]));
+    // The center is still the image
+    assert_eq!(transparent.get_pixel(11, 11).0[3], 255);
+    assert_eq!(transparent.get_pixel(11, 11), white.get_pixel(11, 11));
+}
+
+#[test]
+fn test_edges_blend_into_the_fill_without_dark_fringes() {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 16, Rgb([255, 255, 255])));
+
+    for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+        let rotated = rotate_free(&image, 30.0, interpolation, Fill::Transparent);
+        // Partly covered pixels keep the color and only lose coverage
+        for pixel in rotated.to_rgba8().pixels() {
+            if pixel.0[3] > 16 {
+                assert_eq!(&pixel.0[..3], &[255, 255, 255], "{}", interpolation);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rotation_keeps_the_bit_depth() {
+    let image = DynamicImage::ImageRgb16(image::ImageBuffer::from_pixel(
+        4,
+        4,
+        Rgb([1000u16, 2000, 3000]),
+    ));
+
+    let rotated = rotate_free(&image, 10.0, Interpolation::Bilinear, Fill::Transparent);
+
+    assert!(matches!(rotated, DynamicImage::ImageRgba16(_)));
+    assert!(matches!(
+        rotate_free(
+            &numbered(4, 4),
+            10.0,
+            Interpolation::Nearest,
+            Fill::Transparent
+        ),
+        DynamicImage::ImageRgba8(_)
+    ));
+}
+
+#[test]
+fn test_rotate_free_operation() {
+    let operation = Operation::RotateFree {
+        degrees: 12.5,
+        interpolation: Interpolation::Bicubic,
+        fill: Fill::Color([0, 0, 0, 255]),
+    };
+    assert_eq!(operation.to_string(), "Rotate 12.5");
+    assert!(operation.apply(&numbered(8, 8)).unwrap().width() > 8);
+
+    let invalid = Operation::RotateFree {
+        degrees: f32::NAN,
+        interpolation: Interpolation::Bilinear,
+        fill: Fill::Transparent,
+    };
+    assert!(matches!(
+        invalid.apply(&numbered(8, 8)),
+        Err(HdimError::InvalidAngle(_))
+    ));
+
+    // A long thin line turned a little spans billions of pixels
+    let line = DynamicImage::ImageLuma8(GrayImage::new(100_000, 1));
+    assert_eq!(rotated_size(100_000, 1, 90.0), (1, 100_000));
+    assert!(matches!(
+        operation.apply(&line),
+        Err(HdimError::RotationTooLarge { .. })
+    ));
+}
@@ -0,0 +1,66 @@
+//! Compares the two ways the viewer can draw a frame: rendering an ANSI string
+//! and parsing it back with `ansi-to-tui`, or writing cells into the buffer.
+//! Also measures redrawing a view that is already in the `RenderCache`.
+//!
+//! Run with `cargo bench -p hdim-render --features ratatui`.
+
+use ansi_to_tui::IntoText;
+use criterion::{Criterion, criterion_group, criterion_main};
+use hdim_core::HdimImage;
+use hdim_render::{HalfBlock, ImageWidget, RenderCache, View, render};
+use image::GenericImageView;
+use ratatui::{buffer::Buffer, layout::Rect, text::Text, widgets::Widget};
+use std::hint::black_box;
+
+const COLUMNS: u16 = 240;
+const ROWS: u16 = 70;
+
+fn bench_render_paths(c: &mut Criterion) {
+    let image = image::open("tests/images/4k.jpg").expect("Failed to open 4k.jpg");
+    let (width, height) = image.dimensions();
+    let view = View::fit(width, height, COLUMNS as u32, ROWS as u32);
+    let area = Rect::new(0, 0, COLUMNS, ROWS);
+
+    let mut group = c.benchmark_group("4k.jpg");
+    group.sample_size(20);
+    group.bench_function("ansi string + ansi-to-tui", |b| {
+        b.iter(|| {
+            let ansi = render(black_box(&image), &view).unwrap();
+            let text: Text = ansi.into_text().unwrap();
+            let mut buffer = Buffer::empty(area);
+            text.render(area, &mut buffer);
+            buffer
+        })
+    });
+    group.bench_function("cells into buffer", |b| {
+        b.iter(|| {
+            let widget = ImageWidget::new(&HalfBlock::default(), black_box(&image), &view).unwrap();
+            let mut buffer = Buffer::empty(area);
+            widget.render(area, &mut buffer);
+            buffer
+        })
+    });
+
+    let image =
+        HdimImage::from_path("tests/images/4k.jpg".as_ref()).expect("Failed to open 4k.jpg");
+    let mut cache = RenderCache::new();
+    group.bench_function("cached cells into buffer", |b| {
+        b.iter(|| {
+            let widget = ImageWidget::cached(
+                &mut cache,
+                &HalfBlock::default(),
+                black_box(&image),
+                0,
+                &view,
+            )
+            .unwrap();
+            let mut buffer = Buffer::empty(area);
+            widget.render(area, &mut buffer);
+            buffer
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_render_paths);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+//! Renders views of a 4K photo at several zoom levels.
+//!
+//! Run with `cargo bench -p hdim-render --bench sampling`, add `--features rayon`
+//! to sample on all cores.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use hdim_render::{RenderOptions, View, render_cells};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use std::hint::black_box;
+
+const COLUMNS: u32 = 240;
+const ROWS: u32 = 70;
+
+/// A view of `COLUMNS` x `ROWS` cells in the middle of the image, `zoom` source pixels per column.
+fn centered_view(image: &DynamicImage, zoom: f32) -> View {
+    let (width, height) = image.dimensions();
+    let source_width = ((COLUMNS as f32 * zoom) as u32).clamp(1, width);
+    let source_height = ((ROWS as f32 * 2.0 * zoom) as u32).clamp(1, height);
+    View {
+        source_x: (width - source_width) / 2,
+        source_y: (height - source_height) / 2,
+        source_width,
+        source_height,
+        target_width: COLUMNS,
+        target_height: ROWS,
+    }
+}
+
+fn bench_zoom_levels(c: &mut Criterion) {
+    // The fixture is small, scale it up to the size of an actual 4K photo
+    let rgb = image::open("tests/images/4k.jpg")
+        .expect("Failed to open 4k.jpg")
+        .resize_exact(3840, 2160, FilterType::Triangle);
+    let rgba = DynamicImage::ImageRgba8(rgb.to_rgba8());
+    let options = RenderOptions::default();
+
+    let mut group = c.benchmark_group("4k.jpg");
+    group.sample_size(20);
+    for (name, image) in [("rgb8", &rgb), ("rgba8", &rgba)] {
+        // Whole image, 1:1 pixels, and zoomed in past the image resolution
+        let fit = rgb.width() as f32 / COLUMNS as f32;
+        for (zoom_name, zoom) in [("fit", fit), ("1:1", 1.0), ("4x", 0.25)] {
+            let view = centered_view(image, zoom);
+            group.bench_with_input(BenchmarkId::new(name, zoom_name), &view, |b, view| {
+                b.iter(|| render_cells(black_box(image), view, &options).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_zoom_levels);
+criterion_main!(benches);
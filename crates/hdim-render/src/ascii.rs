@@ -0,0 +1,102 @@
+use crate::cells::{Cell, write_ansi};
+use crate::options::{Background, ColorSpace, Filter};
+use crate::sample::sample_grid;
+use crate::view::View;
+use anyhow::{Result, bail};
+use image::DynamicImage;
+
+/// Characters from dark to bright, for light text on a dark background.
+pub const DEFAULT_RAMP: &str = " .:-=+*#%@";
+
+/// Gradient strength above which `AsciiOptions::edges` draws a line character.
+const EDGE_THRESHOLD: f32 = 240.0;
+
+/// Options for plain text rendering.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsciiOptions {
+    /// Characters from dark to bright. Reverse it for dark text on a light background.
+    pub ramp: String,
+    /// Draw strong edges with `|`, `-`, `/` and `\` along their direction.
+    pub edges: bool,
+}
+
+impl Default for AsciiOptions {
+    fn default() -> Self {
+        Self {
+            ramp: DEFAULT_RAMP.to_string(),
+            edges: false,
+        }
+    }
+}
+
+/// Renders a view as plain text, one character per cell and without escape codes.
+pub fn render_ascii(image: &DynamicImage, view: &View, options: &AsciiOptions) -> Result<String> {
+    write_ansi(
+        &ascii_cells(image, view, options)?,
+        view.target_width as usize,
+    )
+}
+
+/// Renders a view to uncolored cells, row by row.
+pub fn ascii_cells(image: &DynamicImage, view: &View, options: &AsciiOptions) -> Result<Vec<Cell>> {
+    let ramp: Vec<char> = options.ramp.chars().collect();
+    if ramp.is_empty() {
+        bail!("the character ramp is empty");
+    }
+
+    let columns = view.target_width as usize;
+    let rows = view.target_height as usize;
+    let brightness: Vec<f32> = sample_grid(
+        image,
+        view,
+        view.target_width,
+        view.target_height,
+        Filter::Box,
+        ColorSpace::default(),
+        Background::default(),
+    )
+    .into_iter()
+    .map(|[r, g, b]| 0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32)
+    .collect();
+
+    let mut cells = Vec::with_capacity(columns * rows);
+    for y in 0..rows {
+        for x in 0..columns {
+            let edge = if options.edges {
+                edge_glyph(&brightness, columns, rows, x, y)
+            } else {
+                None
+            };
+            let symbol = edge.unwrap_or_else(|| {
+                let index = (brightness[y * columns + x] / 255.0 * (ramp.len() - 1) as f32).round();
+                ramp[(index as usize).min(ramp.len() - 1)]
+            });
+            cells.push(Cell::new(symbol, None, None));
+        }
+    }
+    Ok(cells)
+}
+
+/// Picks a line character along the edge at (x, y), using a Sobel filter on the cell brightness.
+fn edge_glyph(brightness: &[f32], columns: usize, rows: usize, x: usize, y: usize) -> Option<char> {
+    let at = |dx: isize, dy: isize| {
+        let x = (x as isize + dx).clamp(0, columns as isize - 1) as usize;
+        let y = (y as isize + dy).clamp(0, rows as isize - 1) as usize;
+        brightness[y * columns + x]
+    };
+    let gx = at(1, -1) + 2.0 * at(1, 0) + at(1, 1) - at(-1, -1) - 2.0 * at(-1, 0) - at(-1, 1);
+    let gy = at(-1, 1) + 2.0 * at(0, 1) + at(1, 1) - at(-1, -1) - 2.0 * at(0, -1) - at(1, -1);
+    if gx.hypot(gy) < EDGE_THRESHOLD {
+        return None;
+    }
+
+    // The edge runs across the gradient. Rows go down, so a gradient towards
+    // the lower right is an edge from the lower left to the upper right.
+    let angle = gy.atan2(gx).to_degrees().rem_euclid(180.0);
+    Some(match angle {
+        a if !(22.5..157.5).contains(&a) => '|',
+        a if a < 67.5 => '/',
+        a if a < 112.5 => '-',
+        _ => '\\',
+    })
+}
@@ -0,0 +1,39 @@
+use super::Backend;
+use crate::ascii::{AsciiOptions, ascii_cells, render_ascii};
+use crate::cells::{Cell, CellRenderer};
+use crate::view::View;
+use anyhow::Result;
+use image::DynamicImage;
+
+/// Plain text from a character ramp, for places that strip escape codes.
+#[derive(Clone, Debug, Default)]
+pub struct Ascii {
+    pub options: AsciiOptions,
+}
+
+impl Ascii {
+    pub fn new(options: AsciiOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Backend for Ascii {
+    fn render(&self, image: &DynamicImage, view: &View) -> Result<String> {
+        render_ascii(image, view, &self.options)
+    }
+}
+
+impl CellRenderer for Ascii {
+    fn render_cells(&self, image: &DynamicImage, view: &View) -> Result<Vec<Cell>> {
+        ascii_cells(image, view, &self.options)
+    }
+
+    fn cell_pixels(&self) -> (u32, u32) {
+        (1, 1)
+    }
+
+    fn cells_are_local(&self) -> bool {
+        // Edges compare each cell with its neighbours
+        !self.options.edges
+    }
+}
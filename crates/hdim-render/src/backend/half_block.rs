@@ -0,0 +1,47 @@
+use super::Backend;
+use crate::cells::{Cell, CellRenderer};
+use crate::options::{Background, ColorDepth, Dither, Glyphs, RenderOptions, Threshold};
+use crate::view::View;
+use anyhow::Result;
+use image::DynamicImage;
+
+/// Half-block characters, two vertical pixels per cell.
+///
+/// Works in any terminal with color support. `options` can pick glyphs with
+/// more pixels per cell and how many colors are used.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HalfBlock {
+    pub options: RenderOptions,
+}
+
+impl HalfBlock {
+    pub fn new(options: RenderOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Backend for HalfBlock {
+    fn render(&self, image: &DynamicImage, view: &View) -> Result<String> {
+        crate::render_with(image, view, &self.options)
+    }
+}
+
+impl CellRenderer for HalfBlock {
+    fn render_cells(&self, image: &DynamicImage, view: &View) -> Result<Vec<Cell>> {
+        crate::render_cells(image, view, &self.options)
+    }
+
+    fn cell_pixels(&self) -> (u32, u32) {
+        self.options.glyphs.cell_pixels()
+    }
+
+    fn cells_are_local(&self) -> bool {
+        let options = &self.options;
+        // The checkerboard and ordered dithering follow the cells, not the image
+        let braille_is_local = options.glyphs != Glyphs::Braille
+            || matches!(options.braille.threshold, Threshold::Fixed(_));
+        braille_is_local
+            && (options.dither == Dither::None || options.color_depth == ColorDepth::TrueColor)
+            && options.background != Background::Checkerboard
+    }
+}
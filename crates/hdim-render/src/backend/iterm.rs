@@ -0,0 +1,34 @@
+use super::{Backend, scale, view_region};
+use crate::view::View;
+use anyhow::Result;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use image::{DynamicImage, ImageFormat};
+use std::io::Cursor;
+
+/// The iTerm2 inline image protocol (OSC 1337), also understood by WezTerm.
+///
+/// The view is encoded as a PNG and the terminal scales it into the target
+/// cell rectangle.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Iterm;
+
+impl Backend for Iterm {
+    fn render(&self, image: &DynamicImage, view: &View) -> Result<String> {
+        let region = view_region(image, view)?;
+        let mut png = Vec::new();
+        region
+            .image
+            .to_rgba8()
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+        let columns = scale(view.target_width, region.width_fraction);
+        let rows = scale(view.target_height, region.height_fraction);
+        Ok(format!(
+            "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07",
+            png.len(),
+            columns,
+            rows,
+            STANDARD.encode(&png)
+        ))
+    }
+}
@@ -0,0 +1,79 @@
+use super::{Backend, scale, view_region};
+use crate::view::View;
+use anyhow::Result;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use image::DynamicImage;
+use std::fmt::Write;
+
+/// Maximum size of the base64 payload in a single escape sequence, as required by the protocol.
+const CHUNK_SIZE: usize = 4096;
+
+/// The kitty terminal graphics protocol.
+///
+/// The pixels of the view are sent unscaled and the terminal fits them into
+/// the target cell rectangle, so the display is pixel accurate.
+#[derive(Clone, Copy, Debug)]
+pub struct Kitty {
+    /// Id the terminal stores the image under. Rendering again with the same id replaces it.
+    pub image_id: u32,
+}
+
+impl Default for Kitty {
+    fn default() -> Self {
+        Self { image_id: 1 }
+    }
+}
+
+impl Kitty {
+    pub fn new(image_id: u32) -> Self {
+        Self { image_id }
+    }
+
+    /// Displays the previously transmitted image again at the cursor, without resending pixels.
+    pub fn place(&self, view: &View) -> String {
+        format!(
+            "\x1b_Ga=p,i={},c={},r={},q=2\x1b\\",
+            self.image_id, view.target_width, view.target_height
+        )
+    }
+
+    /// Removes the image and all its placements from the terminal.
+    pub fn delete(&self) -> String {
+        format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", self.image_id)
+    }
+}
+
+impl Backend for Kitty {
+    fn render(&self, image: &DynamicImage, view: &View) -> Result<String> {
+        let region = view_region(image, view)?;
+        let pixels = region.image.to_rgba8();
+        let (width, height) = pixels.dimensions();
+        let columns = scale(view.target_width, region.width_fraction);
+        let rows = scale(view.target_height, region.height_fraction);
+        let payload = STANDARD.encode(pixels.as_raw());
+
+        let mut output = String::new();
+        let mut chunks = payload.as_bytes().chunks(CHUNK_SIZE).peekable();
+        let mut first = true;
+        while let Some(chunk) = chunks.next() {
+            let more = u8::from(chunks.peek().is_some());
+            // base64 output is always ASCII
+            let chunk = std::str::from_utf8(chunk)?;
+            if first {
+                write!(
+                    output,
+                    "\x1b_Ga=T,f=32,s={},v={},i={},c={},r={},q=2,m={};{}\x1b\\",
+                    width, height, self.image_id, columns, rows, more, chunk
+                )?;
+                first = false;
+            } else {
+                write!(output, "\x1b_Gm={};{}\x1b\\", more, chunk)?;
+            }
+        }
+        Ok(output)
+    }
+
+    fn clear(&self) -> String {
+        self.delete()
+    }
+}
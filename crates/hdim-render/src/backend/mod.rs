@@ -0,0 +1,68 @@
+pub mod ascii;
+pub mod half_block;
+pub mod iterm;
+pub mod kitty;
+pub mod sixel;
+
+use crate::view::View;
+use anyhow::{Result, bail};
+use image::{DynamicImage, GenericImageView};
+
+pub use ascii::Ascii;
+pub use half_block::HalfBlock;
+pub use iterm::Iterm;
+pub use kitty::Kitty;
+pub use sixel::Sixel;
+
+/// A way of turning a view of an image into terminal output.
+///
+/// Every backend produces a complete escape sequence string for the
+/// `view.target_width` x `view.target_height` cell rectangle starting at the
+/// current cursor position.
+pub trait Backend {
+    fn render(&self, image: &DynamicImage, view: &View) -> Result<String>;
+
+    /// Returns the sequence that removes what `render` drew, for backends whose
+    /// output outlives the text it was drawn over.
+    fn clear(&self) -> String {
+        String::new()
+    }
+}
+
+/// The part of an image covered by a view, clamped to the image bounds.
+pub(crate) struct Region {
+    pub image: DynamicImage,
+    /// Share of `view.target_width` the clamped region covers.
+    pub width_fraction: f64,
+    /// Share of `view.target_height` the clamped region covers.
+    pub height_fraction: f64,
+}
+
+/// Cuts the source rectangle of `view` out of `image`.
+///
+/// A view that extends past the right or bottom edge of the image is clamped, and the
+/// fractions tell how much of the target area the remaining pixels should fill.
+pub(crate) fn view_region(image: &DynamicImage, view: &View) -> Result<Region> {
+    let (image_width, image_height) = image.dimensions();
+    let x = view.source_x.min(image_width);
+    let y = view.source_y.min(image_height);
+    let width = view.source_width.min(image_width - x);
+    let height = view.source_height.min(image_height - y);
+    if width == 0 || height == 0 {
+        bail!(
+            "view lies outside the {}x{} image",
+            image_width,
+            image_height
+        );
+    }
+    Ok(Region {
+        image: image.crop_imm(x, y, width, height),
+        width_fraction: width as f64 / view.source_width as f64,
+        height_fraction: height as f64 / view.source_height as f64,
+    })
+}
+
+/// Scales a number of cells or pixels by a region fraction, keeping at least one.
+pub(crate) fn scale(size: u32, fraction: f64) -> u32 {
+    ((size as f64 * fraction).round() as u32).max(1)
+}
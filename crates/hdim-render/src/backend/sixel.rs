@@ -0,0 +1,277 @@
+use super::{Backend, scale, view_region};
+use crate::view::View;
+use anyhow::{Result, bail};
+use image::{DynamicImage, Rgba, RgbaImage, imageops::FilterType};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Pixels with less alpha than this are left transparent.
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// The DEC Sixel graphics format.
+///
+/// Sixel images are shown at their pixel size, so the view is scaled to the
+/// pixel size of the target cell rectangle and reduced to a palette of at
+/// most `colors` entries with median cut.
+#[derive(Clone, Copy, Debug)]
+pub struct Sixel {
+    /// Number of palette entries, between 2 and 256.
+    pub colors: u16,
+    /// Width of a terminal cell in pixels.
+    pub cell_width: u32,
+    /// Height of a terminal cell in pixels.
+    pub cell_height: u32,
+}
+
+impl Default for Sixel {
+    fn default() -> Self {
+        Self {
+            colors: 256,
+            cell_width: 10,
+            cell_height: 20,
+        }
+    }
+}
+
+impl Backend for Sixel {
+    fn render(&self, image: &DynamicImage, view: &View) -> Result<String> {
+        if !(2..=256).contains(&self.colors) {
+            bail!("sixel palettes have 2 to 256 colors, not {}", self.colors);
+        }
+        if view.target_width * self.cell_width == 0 || view.target_height * self.cell_height == 0 {
+            bail!("cannot render sixel data into an empty area");
+        }
+
+        let region = view_region(image, view)?;
+        let width = scale(view.target_width * self.cell_width, region.width_fraction);
+        let height = scale(
+            view.target_height * self.cell_height,
+            region.height_fraction,
+        );
+        let pixels = region
+            .image
+            .resize_exact(width, height, FilterType::Triangle)
+            .to_rgba8();
+        let palette = median_cut(color_histogram(&pixels), self.colors as usize);
+        let indices = map_to_palette(&pixels, &palette);
+
+        encode(&palette, &indices, width as usize, height as usize)
+    }
+}
+
+/// Counts the opaque colors of `pixels`, reduced to 5 bits per channel to bound the work.
+fn color_histogram(pixels: &RgbaImage) -> Vec<([u8; 3], u64)> {
+    let mut counts: HashMap<[u8; 3], u64> = HashMap::new();
+    for &Rgba([r, g, b, a]) in pixels.pixels() {
+        if a >= ALPHA_THRESHOLD {
+            *counts.entry([r & 0xf8, g & 0xf8, b & 0xf8]).or_insert(0) += 1;
+        }
+    }
+    let mut histogram: Vec<([u8; 3], u64)> = counts.into_iter().collect();
+    // HashMap order is random, sort so the palette is deterministic
+    histogram.sort_unstable();
+    histogram
+}
+
+/// Reduces a color histogram to a palette of at most `max_colors` entries.
+///
+/// The box with the widest channel range is split at its weighted median
+/// until there are enough boxes, and each box contributes its average color.
+fn median_cut(histogram: Vec<([u8; 3], u64)>, max_colors: usize) -> Vec<[u8; 3]> {
+    if histogram.is_empty() {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![histogram];
+    while boxes.len() < max_colors {
+        let Some((index, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(index, colors)| {
+                let (channel, range) = widest_channel(colors);
+                (index, channel, range)
+            })
+            .filter(|&(_, _, range)| range > 0)
+            .max_by_key(|&(_, _, range)| range)
+            .map(|(index, channel, _)| (index, channel))
+        else {
+            break;
+        };
+
+        let mut split = boxes.swap_remove(index);
+        split.sort_by_key(|(color, _)| color[channel]);
+        let half = split.iter().map(|(_, count)| count).sum::<u64>() / 2;
+        let mut seen = 0;
+        let median = split
+            .iter()
+            .position(|(_, count)| {
+                seen += count;
+                seen > half
+            })
+            .unwrap_or(0)
+            .clamp(1, split.len() - 1);
+        let upper = split.split_off(median);
+        boxes.push(split);
+        boxes.push(upper);
+    }
+
+    boxes.iter().map(|colors| average(colors)).collect()
+}
+
+fn widest_channel(colors: &[([u8; 3], u64)]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let min = colors.iter().map(|(c, _)| c[channel]).min().unwrap_or(0);
+            let max = colors.iter().map(|(c, _)| c[channel]).max().unwrap_or(0);
+            (channel, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+/// Weighted average of a box, rounding the 5-bit channels back to the full range.
+fn average(colors: &[([u8; 3], u64)]) -> [u8; 3] {
+    let mut totals = [0u64; 3];
+    let mut count = 0;
+    for (color, weight) in colors {
+        for (total, &value) in totals.iter_mut().zip(color) {
+            *total += (value as u64 | (value as u64 >> 5)) * weight;
+        }
+        count += weight;
+    }
+    totals.map(|total| (total / count.max(1)) as u8)
+}
+
+/// Maps every pixel to its nearest palette entry, or `None` if it is transparent.
+fn map_to_palette(pixels: &RgbaImage, palette: &[[u8; 3]]) -> Vec<Option<u8>> {
+    let mut cache: HashMap<[u8; 3], u8> = HashMap::new();
+    pixels
+        .pixels()
+        .map(|&Rgba([r, g, b, a])| {
+            if a < ALPHA_THRESHOLD {
+                return None;
+            }
+            let color = [r, g, b];
+            Some(
+                *cache
+                    .entry(color)
+                    .or_insert_with(|| nearest(palette, color)),
+            )
+        })
+        .collect()
+}
+
+fn nearest(palette: &[[u8; 3]], color: [u8; 3]) -> u8 {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, entry)| {
+            entry
+                .iter()
+                .zip(color)
+                .map(|(&a, b)| (a as i32 - b as i32).pow(2))
+                .sum::<i32>()
+        })
+        .map_or(0, |(index, _)| index as u8)
+}
+
+fn encode(
+    palette: &[[u8; 3]],
+    indices: &[Option<u8>],
+    width: usize,
+    height: usize,
+) -> Result<String> {
+    let mut output = String::new();
+    // P2 = 1 keeps pixels that are not drawn transparent
+    write!(output, "\x1bP0;1;0q\"1;1;{};{}", width, height)?;
+    for (index, color) in palette.iter().enumerate() {
+        let [r, g, b] = color.map(|c| (c as u32 * 100 + 127) / 255);
+        write!(output, "#{};2;{};{};{}", index, r, g, b)?;
+    }
+
+    // The columns each color draws in the current band, with their bits, left to right
+    let mut columns: Vec<Vec<(usize, u8)>> = vec![Vec::new(); palette.len()];
+    let mut colors = Vec::new();
+    for band_top in (0..height).step_by(6) {
+        let band_height = (height - band_top).min(6);
+        for x in 0..width {
+            // A column of a band has at most six colors
+            let mut column = [(0u8, 0u8); 6];
+            let mut count = 0;
+            for y in 0..band_height {
+                let Some(index) = indices[(band_top + y) * width + x] else {
+                    continue;
+                };
+                match column[..count]
+                    .iter_mut()
+                    .find(|(other, _)| *other == index)
+                {
+                    Some((_, bits)) => *bits |= 1 << y,
+                    None => {
+                        column[count] = (index, 1 << y);
+                        count += 1;
+                    }
+                }
+            }
+            for &(index, bits) in &column[..count] {
+                if columns[index as usize].is_empty() {
+                    colors.push(index);
+                }
+                columns[index as usize].push((x, bits));
+            }
+        }
+
+        colors.sort_unstable();
+        for (i, &color) in colors.iter().enumerate() {
+            if i > 0 {
+                // Return to the start of the band to draw the next color over it
+                output.push('$');
+            }
+            write!(output, "#{}", color)?;
+            write_runs(&mut output, &columns[color as usize])?;
+            columns[color as usize].clear();
+        }
+        colors.clear();
+        output.push('-');
+    }
+
+    output.push_str("\x1b\\");
+    Ok(output)
+}
+
+/// Writes the sixel characters of one color from its drawn columns, as
+/// `(column, bits)` left to right. Columns in between are left blank, columns
+/// after the last one are left out. Runs of four or more are compressed with
+/// `!<count>`.
+fn write_runs(output: &mut String, columns: &[(usize, u8)]) -> Result<()> {
+    fn write_run(output: &mut String, bits: u8, run: usize) -> Result<()> {
+        let symbol = (63 + bits) as char;
+        if run > 3 {
+            write!(output, "!{}{}", run, symbol)?;
+        } else {
+            for _ in 0..run {
+                output.push(symbol);
+            }
+        }
+        Ok(())
+    }
+
+    let mut run = (0u8, 0usize);
+    let mut next = 0;
+    for &(x, bits) in columns {
+        for (bits, count) in [(0, x - next), (bits, 1)] {
+            if count == 0 {
+                continue;
+            }
+            if run.0 == bits {
+                run.1 += count;
+            } else {
+                write_run(output, run.0, run.1)?;
+                run = (bits, count);
+            }
+        }
+        next = x + 1;
+    }
+    write_run(output, run.0, run.1)
+}
@@ -0,0 +1,147 @@
+use crate::cells::Cell;
+use crate::color::quantize;
+use crate::glyphs::glyph;
+use crate::options::{DotColor, Glyphs, RenderOptions, Threshold};
+use crate::sample::sample_grid;
+use crate::view::View;
+use image::DynamicImage;
+
+/// Renders braille dots over the terminal background, 2x4 pixels per cell.
+pub(crate) fn braille_cells(
+    image: &DynamicImage,
+    view: &View,
+    options: &RenderOptions,
+) -> Vec<Cell> {
+    let braille = &options.braille;
+    let (cell_columns, cell_rows) = Glyphs::Braille.cell_pixels();
+    let width = (view.target_width * cell_columns) as usize;
+    let height = (view.target_height * cell_rows) as usize;
+
+    let pixels = sample_grid(
+        image,
+        view,
+        width as u32,
+        height as u32,
+        options.filter,
+        options.averaging,
+        options.background,
+    );
+    let brightness: Vec<u8> = pixels
+        .iter()
+        .map(|&pixel| {
+            let luma = luma(pixel);
+            if braille.invert { 255 - luma } else { luma }
+        })
+        .collect();
+    let dots = match braille.threshold {
+        Threshold::Fixed(threshold) => brightness.iter().map(|&b| b >= threshold).collect(),
+        Threshold::Otsu => {
+            let threshold = otsu_threshold(&brightness);
+            brightness.iter().map(|&b| b > threshold).collect()
+        }
+        Threshold::Dithered => dither(&brightness, width),
+    };
+
+    // Collect the dot pattern and color of every cell
+    let columns = view.target_width as usize;
+    let mut patterns = Vec::with_capacity(columns * view.target_height as usize);
+    let mut colors = Vec::with_capacity(patterns.capacity());
+    for cell_y in 0..view.target_height as usize {
+        for cell_x in 0..columns {
+            let mut pattern = 0u8;
+            let mut totals = [0u32; 3];
+            for bit in 0..8 {
+                let x = cell_x * 2 + bit % 2;
+                let y = cell_y * 4 + bit / 2;
+                if dots[y * width + x] {
+                    pattern |= 1 << bit;
+                    for (total, &c) in totals.iter_mut().zip(&pixels[y * width + x]) {
+                        *total += c as u32;
+                    }
+                }
+            }
+            let count = pattern.count_ones().max(1);
+            colors.push(match braille.color {
+                DotColor::Average => totals.map(|total| (total / count) as u8),
+                DotColor::Single(color) => color,
+            });
+            patterns.push(pattern);
+        }
+    }
+    let colors = quantize(&colors, columns, options.color_depth, options.dither);
+
+    colors
+        .into_iter()
+        .zip(patterns)
+        .map(|(color, pattern)| {
+            if pattern == 0 {
+                Cell::new(' ', None, None)
+            } else {
+                Cell::new(glyph(Glyphs::Braille, pattern), Some(color), None)
+            }
+        })
+        .collect()
+}
+
+/// Perceived brightness with the Rec. 709 weights.
+fn luma([r, g, b]: [u8; 3]) -> u8 {
+    (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32).round() as u8
+}
+
+/// Finds the brightness that best splits `values` into a dark and a bright class.
+///
+/// Maximizes the variance between the two classes, values above the result are bright.
+pub fn otsu_threshold(values: &[u8]) -> u8 {
+    let mut histogram = [0u64; 256];
+    for &value in values {
+        histogram[value as usize] += 1;
+    }
+    let total = values.len() as f64;
+    let sum: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(value, &count)| value as f64 * count as f64)
+        .sum();
+
+    let mut best = (0, 0.0);
+    let mut dark_count = 0.0;
+    let mut dark_sum = 0.0;
+    for (value, &count) in histogram.iter().enumerate() {
+        dark_count += count as f64;
+        dark_sum += value as f64 * count as f64;
+        let bright_count = total - dark_count;
+        if dark_count == 0.0 || bright_count == 0.0 {
+            continue;
+        }
+        let dark_mean = dark_sum / dark_count;
+        let bright_mean = (sum - dark_sum) / bright_count;
+        let variance = dark_count * bright_count * (dark_mean - bright_mean).powi(2);
+        if variance > best.1 {
+            best = (value as u8, variance);
+        }
+    }
+    best.0
+}
+
+/// Floyd-Steinberg dithering of brightness to dots.
+fn dither(brightness: &[u8], width: usize) -> Vec<bool> {
+    let mut values: Vec<f32> = brightness.iter().map(|&b| b as f32).collect();
+    let mut dots = Vec::with_capacity(values.len());
+    for i in 0..values.len() {
+        let (x, y) = (i % width, i / width);
+        let dot = values[i] >= 128.0;
+        dots.push(dot);
+
+        let error = values[i] - if dot { 255.0 } else { 0.0 };
+        for (dx, dy, weight) in [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)] {
+            let nx = x as isize + dx;
+            if nx < 0 || nx as usize >= width {
+                continue;
+            }
+            if let Some(value) = values.get_mut((y + dy) * width + nx as usize) {
+                *value += error * weight / 16.0;
+            }
+        }
+    }
+    dots
+}
@@ -0,0 +1,178 @@
+use crate::cells::{Cell, CellRenderer};
+use crate::view::View;
+use anyhow::Result;
+use hdim_core::HdimImage;
+use std::ops::Range;
+
+/// The cells of the last rendered view, so unchanged frames cost nothing.
+///
+/// Entries are keyed by the view and a generation number the caller increments
+/// whenever the image changes. When the view moved by whole cells and the
+/// renderer's cells are local (`CellRenderer::cells_are_local`), the cells that
+/// stay visible are shifted and only the newly exposed rows and columns are
+/// rendered.
+///
+/// Call `clear` when the renderer changes.
+#[derive(Clone, Debug, Default)]
+pub struct RenderCache {
+    last: Option<Rendered>,
+}
+
+#[derive(Clone, Debug)]
+struct Rendered {
+    view: View,
+    generation: u64,
+    cells: Vec<Cell>,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets the cached cells.
+    pub fn clear(&mut self) {
+        self.last = None;
+    }
+
+    /// Renders `view` of `image` like `CellRenderer::render_pyramid`, reusing what it can.
+    pub fn render(
+        &mut self,
+        renderer: &dyn CellRenderer,
+        image: &HdimImage,
+        generation: u64,
+        view: &View,
+    ) -> Result<&[Cell]> {
+        let cells = match self.last.take() {
+            Some(last) if last.generation == generation && last.view == *view => last.cells,
+            Some(last) if last.generation == generation && renderer.cells_are_local() => {
+                match cell_offset(&last.view, view) {
+                    Some(offset) => shift(renderer, image, &last, view, offset)?,
+                    None => renderer.render_pyramid(image, view)?,
+                }
+            }
+            _ => renderer.render_pyramid(image, view)?,
+        };
+        let rendered = self.last.insert(Rendered {
+            view: *view,
+            generation,
+            cells,
+        });
+        Ok(&rendered.cells)
+    }
+}
+
+/// How many cells `to` is right of and below `from`, if it moved by whole cells and they overlap.
+fn cell_offset(from: &View, to: &View) -> Option<(i64, i64)> {
+    let same_scale = from.source_width == to.source_width
+        && from.source_height == to.source_height
+        && from.target_width == to.target_width
+        && from.target_height == to.target_height;
+    if !same_scale || to.source_width == 0 || to.source_height == 0 {
+        return None;
+    }
+    let axis = |from: u32, to: u32, source: u32, target: u32| {
+        let pixels = (to as i64 - from as i64) * target as i64;
+        let cells = pixels / source as i64;
+        (pixels % source as i64 == 0 && cells.abs() < target as i64).then_some(cells)
+    };
+    Some((
+        axis(from.source_x, to.source_x, to.source_width, to.target_width)?,
+        axis(
+            from.source_y,
+            to.source_y,
+            to.source_height,
+            to.target_height,
+        )?,
+    ))
+}
+
+/// Moves the cells of `last` by `offset` and renders the cells of `view` it did not cover.
+fn shift(
+    renderer: &dyn CellRenderer,
+    image: &HdimImage,
+    last: &Rendered,
+    view: &View,
+    (columns, rows): (i64, i64),
+) -> Result<Vec<Cell>> {
+    let width = view.target_width as i64;
+    let height = view.target_height as i64;
+    let mut cells = vec![Cell::new(' ', None, None); (width * height) as usize];
+    for y in 0..height {
+        let from_y = y + rows;
+        if !(0..height).contains(&from_y) {
+            continue;
+        }
+        for x in 0..width {
+            let from_x = x + columns;
+            if (0..width).contains(&from_x) {
+                cells[(y * width + x) as usize] = last.cells[(from_y * width + from_x) as usize];
+            }
+        }
+    }
+
+    // Whole exposed rows first, then the exposed columns of the rows in between
+    let exposed = |offset: i64, length: i64| match offset {
+        0 => 0..0,
+        offset if offset > 0 => (length - offset) as u32..length as u32,
+        offset => 0..(-offset) as u32,
+    };
+    let exposed_rows = exposed(rows, height);
+    let kept_rows = if rows > 0 {
+        0..exposed_rows.start
+    } else {
+        exposed_rows.end..height as u32
+    };
+    render_into(
+        renderer,
+        image,
+        view,
+        &mut cells,
+        0..view.target_width,
+        exposed_rows,
+    )?;
+    render_into(
+        renderer,
+        image,
+        view,
+        &mut cells,
+        exposed(columns, width),
+        kept_rows,
+    )?;
+    Ok(cells)
+}
+
+/// Renders the cells `columns` x `rows` of `view` into `cells`.
+///
+/// The rectangle has to start and end on whole source pixels.
+fn render_into(
+    renderer: &dyn CellRenderer,
+    image: &HdimImage,
+    view: &View,
+    cells: &mut [Cell],
+    columns: Range<u32>,
+    rows: Range<u32>,
+) -> Result<()> {
+    if columns.is_empty() || rows.is_empty() {
+        return Ok(());
+    }
+    let pixel =
+        |cell: u32, source: u32, target: u32| (cell as u64 * source as u64 / target as u64) as u32;
+    let left = pixel(columns.start, view.source_width, view.target_width);
+    let top = pixel(rows.start, view.source_height, view.target_height);
+    let part = View {
+        source_x: view.source_x + left,
+        source_y: view.source_y + top,
+        source_width: pixel(columns.end, view.source_width, view.target_width) - left,
+        source_height: pixel(rows.end, view.source_height, view.target_height) - top,
+        target_width: columns.len() as u32,
+        target_height: rows.len() as u32,
+    };
+    let rendered = renderer.render_pyramid(image, &part)?;
+    let width = view.target_width as usize;
+    for (y, row) in rows.zip(rendered.chunks(columns.len())) {
+        let start = y as usize * width + columns.start as usize;
+        cells[start..start + row.len()].copy_from_slice(row);
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use crate::color::TerminalColor;
+use crate::view::View;
+use anyhow::Result;
+use hdim_core::HdimImage;
+use image::DynamicImage;
+
+/// One terminal cell of a rendered view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: char,
+    /// `None` keeps the terminal's default color.
+    pub fg: Option<TerminalColor>,
+    /// `None` keeps the terminal's default color.
+    pub bg: Option<TerminalColor>,
+}
+
+impl Cell {
+    pub fn new(symbol: char, fg: Option<TerminalColor>, bg: Option<TerminalColor>) -> Self {
+        Self { symbol, fg, bg }
+    }
+}
+
+/// Backends that draw with characters, so their output can be written to a cell grid.
+pub trait CellRenderer: crate::Backend {
+    /// Renders the view to `view.target_width` x `view.target_height` cells, row by row.
+    fn render_cells(&self, image: &DynamicImage, view: &View) -> Result<Vec<Cell>>;
+
+    /// The number of pixels a cell shows, as (columns, rows).
+    fn cell_pixels(&self) -> (u32, u32) {
+        (1, 2)
+    }
+
+    /// Whether every cell only depends on the pixels it covers, so the cells of
+    /// overlapping views are interchangeable. See `RenderCache`.
+    ///
+    /// Dithering, or a threshold chosen for the whole view, is not local.
+    fn cells_are_local(&self) -> bool {
+        false
+    }
+
+    /// Like `render_cells`, but reads the coarsest pyramid level of `image` that
+    /// still has a pixel for every one the cells show.
+    ///
+    /// Zoomed out views then cost about as much as the terminal is large,
+    /// instead of as much as the image.
+    fn render_pyramid(&self, image: &HdimImage, view: &View) -> Result<Vec<Cell>> {
+        let (cell_columns, cell_rows) = self.cell_pixels();
+        let scale = (view.source_width as f32 / (view.target_width * cell_columns).max(1) as f32)
+            .min(view.source_height as f32 / (view.target_height * cell_rows).max(1) as f32);
+        match image.level_for(scale) {
+            Some(level) => self.render_cells(&level.image, &view.scaled_down(level.factor)),
+            None => self.render_cells(&image.data, view),
+        }
+    }
+}
+
+/// Writes cells as lines of text with SGR color escapes.
+///
+/// Lines that set a color end with a reset so the colors do not leak into the next line.
+pub fn write_ansi(cells: &[Cell], columns: usize) -> Result<String> {
+    let mut output = String::new();
+    for row in cells.chunks(columns.max(1)) {
+        let mut current_fg = None;
+        let mut colored = false;
+        for cell in row {
+            if let Some(bg) = cell.bg {
+                output.push_str("\x1b[");
+                bg.write_sgr(&mut output, true)?;
+                output.push('m');
+                colored = true;
+            }
+            // Without a background the foreground only changes when it differs,
+            // a background is always followed by its foreground
+            if let Some(fg) = cell.fg
+                && (cell.bg.is_some() || current_fg != Some(fg))
+            {
+                output.push_str("\x1b[");
+                fg.write_sgr(&mut output, false)?;
+                output.push('m');
+                current_fg = Some(fg);
+                colored = true;
+            }
+            output.push(cell.symbol);
+        }
+        if colored {
+            output.push_str("\x1b[0m");
+        }
+        output.push('\n');
+    }
+    Ok(output)
+}
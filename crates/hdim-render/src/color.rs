@@ -0,0 +1,206 @@
+use std::fmt;
+
+use crate::options::{ColorDepth, Dither};
+
+/// Channel values of the xterm 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 ANSI colors as xterm shows them by default.
+const ANSI16: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+/// 4x4 Bayer threshold matrix.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// A color as the terminal is told to show it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminalColor {
+    Rgb([u8; 3]),
+    /// Index into the xterm 256 color palette, or into the 16 ANSI colors.
+    Indexed(u8),
+}
+
+impl TerminalColor {
+    /// The RGB value the terminal shows, assuming the default xterm palette.
+    pub fn rgb(self) -> [u8; 3] {
+        match self {
+            Self::Rgb(rgb) => rgb,
+            Self::Indexed(index) => palette_rgb(index),
+        }
+    }
+
+    /// Writes the SGR parameters that set this color as background, or as foreground.
+    pub fn write_sgr(self, output: &mut impl fmt::Write, background: bool) -> fmt::Result {
+        match self {
+            Self::Rgb([r, g, b]) => {
+                write!(
+                    output,
+                    "{};2;{};{};{}",
+                    if background { 48 } else { 38 },
+                    r,
+                    g,
+                    b
+                )
+            }
+            Self::Indexed(index) if index >= 16 => {
+                write!(output, "{};5;{}", if background { 48 } else { 38 }, index)
+            }
+            // The 16 colors have their own codes, which every terminal understands
+            Self::Indexed(index) => {
+                let base = match (background, index >= 8) {
+                    (false, false) => 30,
+                    (false, true) => 90,
+                    (true, false) => 40,
+                    (true, true) => 100,
+                };
+                write!(output, "{}", base + index % 8)
+            }
+        }
+    }
+}
+
+/// Returns the RGB value of an xterm 256 palette index from 16 to 255, or of an ANSI color below 16.
+pub fn palette_rgb(index: u8) -> [u8; 3] {
+    match index {
+        0..=15 => ANSI16[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            [
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            ]
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            [gray, gray, gray]
+        }
+    }
+}
+
+/// Finds the nearest xterm 256 palette color, from the color cube or the grayscale ramp.
+///
+/// Indices 0 to 15 are never returned because terminal themes redefine them.
+pub fn nearest_256(color: [u8; 3]) -> u8 {
+    let cube_index = |value: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|&(_, &level)| level.abs_diff(value))
+            .map_or(0, |(index, _)| index as u8)
+    };
+    let [r, g, b] = color.map(cube_index);
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    let average = color.iter().map(|&c| c as u32).sum::<u32>() / 3;
+    let gray = 232 + ((average as i32 - 3) / 10).clamp(0, 23) as u8;
+
+    if distance(palette_rgb(gray), color) < distance(palette_rgb(cube), color) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Finds the nearest of the 16 ANSI colors.
+pub fn nearest_16(color: [u8; 3]) -> u8 {
+    (0..16u8)
+        .min_by_key(|&index| distance(ANSI16[index as usize], color))
+        .unwrap_or(0)
+}
+
+/// Squared distance, weighted for how sensitive the eye is to each channel.
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    let [dr, dg, db] = [0, 1, 2].map(|i| (a[i] as i32 - b[i] as i32).pow(2) as u32);
+    2 * dr + 4 * dg + 3 * db
+}
+
+/// Maps a grid of pixels, `width` wide and stored row by row, to terminal colors.
+pub fn quantize(
+    pixels: &[[u8; 3]],
+    width: usize,
+    depth: ColorDepth,
+    dither: Dither,
+) -> Vec<TerminalColor> {
+    let nearest = match depth {
+        ColorDepth::TrueColor => return pixels.iter().map(|&p| TerminalColor::Rgb(p)).collect(),
+        ColorDepth::Ansi256 => nearest_256,
+        ColorDepth::Ansi16 => nearest_16,
+    };
+
+    match dither {
+        Dither::None => pixels
+            .iter()
+            .map(|&p| TerminalColor::Indexed(nearest(p)))
+            .collect(),
+        Dither::Ordered => {
+            // Roughly the distance between neighbouring palette colors
+            let spread = match depth {
+                ColorDepth::Ansi16 => 128.0,
+                _ => 40.0,
+            };
+            pixels
+                .iter()
+                .enumerate()
+                .map(|(i, &p)| {
+                    let (x, y) = (i % width, i / width);
+                    let threshold = (BAYER[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5;
+                    let p = p.map(|c| (c as f32 + threshold * spread).clamp(0.0, 255.0) as u8);
+                    TerminalColor::Indexed(nearest(p))
+                })
+                .collect()
+        }
+        Dither::FloydSteinberg => floyd_steinberg(pixels, width, nearest),
+    }
+}
+
+fn floyd_steinberg(
+    pixels: &[[u8; 3]],
+    width: usize,
+    nearest: fn([u8; 3]) -> u8,
+) -> Vec<TerminalColor> {
+    let mut values: Vec<[f32; 3]> = pixels.iter().map(|p| p.map(|c| c as f32)).collect();
+    let mut output = Vec::with_capacity(pixels.len());
+    for i in 0..values.len() {
+        let (x, y) = (i % width, i / width);
+        let old = values[i].map(|c| c.clamp(0.0, 255.0).round() as u8);
+        let index = nearest(old);
+        let new = palette_rgb(index);
+        output.push(TerminalColor::Indexed(index));
+
+        let error = [0, 1, 2].map(|c| values[i][c] - new[c] as f32);
+        let mut spread = |dx: isize, dy: usize, weight: f32| {
+            let nx = x as isize + dx;
+            if nx < 0 || nx as usize >= width {
+                return;
+            }
+            let j = (y + dy) * width + nx as usize;
+            if let Some(value) = values.get_mut(j) {
+                for c in 0..3 {
+                    value[c] += error[c] * weight;
+                }
+            }
+        };
+        spread(1, 0, 7.0 / 16.0);
+        spread(-1, 1, 3.0 / 16.0);
+        spread(0, 1, 5.0 / 16.0);
+        spread(1, 1, 1.0 / 16.0);
+    }
+    output
+}
@@ -0,0 +1,261 @@
+use std::time::Duration;
+
+/// The render backends in order of preference.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BackendKind {
+    /// The kitty graphics protocol.
+    Kitty,
+    /// DEC Sixel graphics.
+    Sixel,
+    /// iTerm2 inline images (OSC 1337).
+    Iterm,
+    /// Half blocks with 24-bit colors.
+    TrueColor,
+    /// Half blocks with the xterm 256 color palette.
+    Ansi256,
+    /// Half blocks with the 16 standard ANSI colors.
+    Ansi16,
+    /// Plain text without colors. Never detected, only chosen explicitly.
+    Ascii,
+}
+
+/// The environment variables that hint at terminal capabilities.
+#[derive(Clone, Debug, Default)]
+pub struct Environment {
+    pub colorterm: Option<String>,
+    pub term: Option<String>,
+    pub term_program: Option<String>,
+    /// Whether we run inside tmux, which does not pass graphics through by default.
+    pub tmux: bool,
+}
+
+impl Environment {
+    pub fn from_env() -> Self {
+        Self {
+            colorterm: std::env::var("COLORTERM").ok(),
+            term: std::env::var("TERM").ok(),
+            term_program: std::env::var("TERM_PROGRAM").ok(),
+            tmux: std::env::var_os("TMUX").is_some(),
+        }
+    }
+}
+
+/// Capabilities reported by the terminal in reply to `QUERIES`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Replies {
+    /// The kitty graphics query was answered with OK.
+    pub kitty: bool,
+    /// The primary device attributes (DA1) list attribute 4.
+    pub sixel: bool,
+    /// XTGETTCAP reported the `RGB` or `Tc` capability.
+    pub truecolor: bool,
+    /// A DA1 reply was seen at all, i.e. the terminal answers queries.
+    pub answered: bool,
+}
+
+/// Queries sent to the terminal: a kitty graphics query, XTGETTCAP for `RGB`
+/// and `Tc`, and finally DA1. Practically every terminal answers DA1, so its
+/// reply marks the end of the answers.
+pub const QUERIES: &str =
+    "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\\x1bP+q524742\x1b\\\x1bP+q5463\x1b\\\x1b[c";
+
+/// Parses the raw bytes the terminal sent back after `QUERIES`.
+pub fn parse_replies(replies: &str) -> Replies {
+    let kitty = replies.contains("\x1b_Gi=31;OK");
+    let truecolor = ["524742", "5463"]
+        .iter()
+        .any(|name| replies.contains(&format!("\x1bP1+r{}", name)));
+
+    let da1 = da1_attributes(replies);
+    Replies {
+        kitty,
+        sixel: da1
+            .as_ref()
+            .is_some_and(|attributes| attributes.contains(&4)),
+        truecolor,
+        answered: da1.is_some(),
+    }
+}
+
+/// Extracts the attributes of a `CSI ? Ps ; ... c` reply.
+fn da1_attributes(replies: &str) -> Option<Vec<u16>> {
+    let start = replies.find("\x1b[?")? + 3;
+    let rest = &replies[start..];
+    let end = rest.find('c')?;
+    let attributes = &rest[..end];
+    if !attributes.chars().all(|c| c.is_ascii_digit() || c == ';') {
+        return None;
+    }
+    Some(
+        attributes
+            .split(';')
+            .filter_map(|attribute| attribute.parse().ok())
+            .collect(),
+    )
+}
+
+/// Whether `replies` contains the DA1 reply that ends the answers to `QUERIES`.
+pub fn is_complete(replies: &str) -> bool {
+    da1_attributes(replies).is_some()
+}
+
+/// Picks the best backend from the environment and the optional query replies.
+pub fn choose(env: &Environment, replies: Option<&Replies>) -> BackendKind {
+    let term = env.term.as_deref().unwrap_or_default();
+    let term_program = env.term_program.as_deref().unwrap_or_default();
+    let replies = replies.copied().unwrap_or_default();
+
+    // Graphics need passthrough in tmux, fall back to characters there
+    if !env.tmux {
+        if replies.kitty || term.contains("kitty") || term_program == "ghostty" {
+            return BackendKind::Kitty;
+        }
+        if replies.sixel || term.contains("foot") || term.contains("mlterm") {
+            return BackendKind::Sixel;
+        }
+        if term_program == "iTerm.app" || term_program == "WezTerm" {
+            return BackendKind::Iterm;
+        }
+    }
+
+    let colorterm = env.colorterm.as_deref().unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" || replies.truecolor {
+        BackendKind::TrueColor
+    } else if term.contains("256") || term_program == "Apple_Terminal" {
+        BackendKind::Ansi256
+    } else if term.is_empty() || term == "linux" || term == "dumb" || term.starts_with("vt") {
+        BackendKind::Ansi16
+    } else {
+        BackendKind::Ansi256
+    }
+}
+
+/// Detects the best backend for the terminal on stdin/stdout.
+///
+/// Queries the terminal and waits up to `timeout` for the replies. When the
+/// terminal cannot be queried the choice is made from the environment alone.
+pub fn detect(timeout: Duration) -> BackendKind {
+    let env = Environment::from_env();
+    let replies = if env.tmux {
+        None
+    } else {
+        query(QUERIES, timeout).map(|replies| parse_replies(&replies))
+    };
+    choose(&env, replies.as_ref())
+}
+
+/// Asks for the default background color (OSC 11), followed by DA1 to mark the end of the reply.
+pub const BACKGROUND_QUERY: &str = "\x1b]11;?\x1b\\\x1b[c";
+
+/// Parses an OSC 11 reply like `ESC ] 11 ; rgb:ffff/8080/0000 ST` into an sRGB color.
+pub fn parse_background(replies: &str) -> Option<[u8; 3]> {
+    let start = replies.find("\x1b]11;rgb:")? + 9;
+    let rest = &replies[start..];
+    // Terminated by either BEL or ST
+    let end = rest.find(['\x07', '\x1b'])?;
+    let channels: Vec<u8> = rest[..end]
+        .split('/')
+        .map(scale_channel)
+        .collect::<Option<_>>()?;
+    channels.try_into().ok()
+}
+
+/// Scales a channel of 1 to 4 hex digits to 8 bits.
+fn scale_channel(hex: &str) -> Option<u8> {
+    if hex.is_empty() || hex.len() > 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let max = (1 << (4 * hex.len())) - 1;
+    Some(((value * 255 + max / 2) / max) as u8)
+}
+
+/// Asks the terminal on stdin/stdout for its default background color.
+///
+/// Returns `None` when there is no terminal or it does not report the color in time.
+pub fn background(timeout: Duration) -> Option<[u8; 3]> {
+    query(BACKGROUND_QUERY, timeout).and_then(|replies| parse_background(&replies))
+}
+
+/// How much longer replies are waited for once `query` gives up, so a slow
+/// terminal does not leave them to be read as key presses.
+#[cfg(unix)]
+const LATE_REPLIES: Duration = Duration::from_millis(500);
+
+/// Sends `queries` to /dev/tty in raw mode and returns what the terminal
+/// answers, up to and including the DA1 reply.
+///
+/// `queries` must end with a DA1 request (`ESC [ c`), whose reply tells that
+/// the answers to everything before it are complete. Returns `None` when there
+/// is no terminal or the DA1 reply does not arrive within `timeout`. Replies
+/// that are late are read and dropped, along with anything else still waiting
+/// on the terminal.
+#[cfg(unix)]
+pub fn query(queries: &str, timeout: Duration) -> Option<String> {
+    use std::fs::{File, OpenOptions};
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::Instant;
+
+    /// Reads into `replies` until they are complete, the deadline passes or the terminal fails.
+    fn read_replies(tty: &mut File, replies: &mut Vec<u8>, deadline: Instant) {
+        let mut buffer = [0u8; 256];
+        while !is_complete(&String::from_utf8_lossy(replies)) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            let mut fd = libc::pollfd {
+                fd: tty.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: `fd` is a valid pollfd for an open file and we pass a count of one.
+            let ready = unsafe { libc::poll(&mut fd, 1, remaining.as_millis() as libc::c_int) };
+            if ready <= 0 {
+                break;
+            }
+            match tty.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => replies.extend_from_slice(&buffer[..n]),
+            }
+        }
+    }
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let was_raw = crossterm::terminal::is_raw_mode_enabled().ok()?;
+    if !was_raw {
+        crossterm::terminal::enable_raw_mode().ok()?;
+    }
+
+    let mut replies = Vec::new();
+    let deadline = Instant::now() + timeout;
+    let sent = tty
+        .write_all(queries.as_bytes())
+        .and_then(|()| tty.flush())
+        .is_ok();
+    if sent {
+        read_replies(&mut tty, &mut replies, deadline);
+    }
+    let complete = is_complete(&String::from_utf8_lossy(&replies));
+    if sent && !complete {
+        read_replies(&mut tty, &mut replies, deadline + LATE_REPLIES);
+        // SAFETY: the descriptor belongs to `tty`, which is open.
+        unsafe { libc::tcflush(tty.as_raw_fd(), libc::TCIFLUSH) };
+    }
+
+    if !was_raw {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+    complete.then(|| String::from_utf8_lossy(&replies).into_owned())
+}
+
+/// Querying the terminal is only supported on Unix, elsewhere only the environment is used.
+#[cfg(not(unix))]
+pub fn query(_queries: &str, _timeout: Duration) -> Option<String> {
+    None
+}
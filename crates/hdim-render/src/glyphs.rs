@@ -0,0 +1,126 @@
+use crate::options::Glyphs;
+
+/// Quadrant characters indexed by their pattern: bit 0 is the upper left pixel,
+/// then upper right, lower left and lower right.
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// Left and right half blocks, the only sextant patterns outside the sextant range.
+const SEXTANT_LEFT: u8 = 0b010101;
+const SEXTANT_RIGHT: u8 = 0b101010;
+
+/// Octant patterns drawn with older characters, sorted by pattern. The octant
+/// range at U+1CD00 skips them.
+const OCTANTS_ELSEWHERE: [(u8, char); 26] = [
+    (0b00000000, ' '),
+    (0b00000001, '\u{1CEA8}'),
+    (0b00000010, '\u{1CEAB}'),
+    (0b00000011, '\u{1FB82}'),
+    (0b00000101, '▘'),
+    (0b00001010, '▝'),
+    (0b00001111, '▀'),
+    (0b00010100, '\u{1FBE6}'),
+    (0b00101000, '\u{1FBE7}'),
+    (0b00111111, '\u{1FB85}'),
+    (0b01000000, '\u{1CEA3}'),
+    (0b01010000, '▖'),
+    (0b01010101, '▌'),
+    (0b01011010, '▞'),
+    (0b01011111, '▛'),
+    (0b10000000, '\u{1CEA0}'),
+    (0b10100000, '▗'),
+    (0b10100101, '▚'),
+    (0b10101010, '▐'),
+    (0b10101111, '▜'),
+    (0b11000000, '▂'),
+    (0b11110000, '▄'),
+    (0b11110101, '▙'),
+    (0b11111010, '▟'),
+    (0b11111100, '▆'),
+    (0b11111111, '█'),
+];
+
+/// Returns the character that shows the foreground where `pattern` has bits set.
+///
+/// Pixels are numbered row by row from the upper left, bit 0 being the first.
+pub fn glyph(glyphs: Glyphs, pattern: u8) -> char {
+    match glyphs {
+        Glyphs::HalfBlock => [' ', '▀', '▄', '█'][pattern as usize & 0b11],
+        Glyphs::Quadrant => QUADRANTS[pattern as usize & 0b1111],
+        // Dots 1 to 6 run down the left then the right column, dots 7 and 8 are the bottom row
+        Glyphs::Braille => {
+            const DOTS: [u8; 8] = [0, 3, 1, 4, 2, 5, 6, 7];
+            let bits = DOTS
+                .iter()
+                .enumerate()
+                .filter(|&(pixel, _)| pattern & (1 << pixel) != 0)
+                .fold(0, |bits, (_, &dot)| bits | (1 << dot));
+            char::from_u32(0x2800 + bits).unwrap_or(' ')
+        }
+        Glyphs::Sextant => match pattern & 0b111111 {
+            0 => ' ',
+            SEXTANT_LEFT => '▌',
+            SEXTANT_RIGHT => '▐',
+            0b111111 => '█',
+            pattern => {
+                // U+1FB00 starts at pattern 1 and skips the two half blocks
+                let skipped =
+                    u32::from(pattern > SEXTANT_LEFT) + u32::from(pattern > SEXTANT_RIGHT);
+                char::from_u32(0x1FB00 + pattern as u32 - 1 - skipped).unwrap_or(' ')
+            }
+        },
+        Glyphs::Octant => match OCTANTS_ELSEWHERE.binary_search_by_key(&pattern, |&(p, _)| p) {
+            Ok(index) => OCTANTS_ELSEWHERE[index].1,
+            // The index is how many of the skipped patterns come before this one
+            Err(skipped) => {
+                char::from_u32(0x1CD00 + pattern as u32 - skipped as u32).unwrap_or(' ')
+            }
+        },
+    }
+}
+
+/// Splits the pixels of a cell into the two colors that fit them best.
+///
+/// Every split is tried and the one with the least squared error against the
+/// group averages wins. Returns the pattern of pixels in the foreground group
+/// and the (foreground, background) colors.
+pub fn fit_two_colors(pixels: &[[u8; 3]]) -> (u8, [u8; 3], [u8; 3]) {
+    let count = pixels.len();
+    let mut best = (0, f32::MAX, [0; 3], [0; 3]);
+    // The last pixel is always in the background, so each split is tried once
+    for pattern in 0..(1u8 << (count - 1)) {
+        let mut sums = [[0u32; 3]; 2];
+        let mut sizes = [0u32; 2];
+        for (i, pixel) in pixels.iter().enumerate() {
+            let group = usize::from(pattern & (1 << i) != 0);
+            sizes[group] += 1;
+            for c in 0..3 {
+                sums[group][c] += pixel[c] as u32;
+            }
+        }
+        let means = [0, 1].map(|group| sums[group].map(|sum| sum / sizes[group].max(1)));
+
+        let error: f32 = pixels
+            .iter()
+            .enumerate()
+            .map(|(i, pixel)| {
+                let mean = means[usize::from(pattern & (1 << i) != 0)];
+                (0..3)
+                    .map(|c| (pixel[c] as f32 - mean[c] as f32).powi(2))
+                    .sum::<f32>()
+            })
+            .sum();
+        if error < best.1 {
+            best = (
+                pattern,
+                error,
+                means[1].map(|c| c as u8),
+                means[0].map(|c| c as u8),
+            );
+        }
+    }
+
+    let (pattern, _, foreground, background) = best;
+    (pattern, foreground, background)
+}
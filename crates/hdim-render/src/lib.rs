@@ -1,65 +1,141 @@
+pub mod ascii;
+pub mod backend;
+pub mod braille;
+pub mod cache;
+pub mod cells;
+pub mod color;
+pub mod detect;
+pub mod glyphs;
+pub mod options;
 pub mod pixel;
+pub mod sample;
+mod space;
 pub mod view;
+#[cfg(feature = "ratatui")]
+pub mod widget;
 
 use anyhow::Result;
 use image::DynamicImage;
-use std::fmt::Write;
 
-use self::pixel::get_average_rgb;
+pub use self::ascii::AsciiOptions;
+pub use self::backend::{Ascii, Backend, HalfBlock, Iterm, Kitty, Sixel};
+use self::braille::braille_cells;
+pub use self::cache::RenderCache;
+pub use self::cells::{Cell, CellRenderer, write_ansi};
+use self::color::{TerminalColor, quantize};
+pub use self::detect::BackendKind;
+use self::glyphs::{fit_two_colors, glyph};
+pub use self::options::{
+    Background, BrailleOptions, ColorDepth, ColorSpace, Dither, DotColor, Filter, Glyphs,
+    RenderOptions, Threshold,
+};
+use self::sample::sample_grid;
 pub use self::view::View;
+#[cfg(feature = "ratatui")]
+pub use self::widget::ImageWidget;
 
 /// Renders a portion of an image to a string using half-block characters.
 ///
 /// The rendering is defined by the `View` struct, which maps a source rectangle
 /// from the image to a target area in the terminal.
 pub fn render(image: &DynamicImage, view: &View) -> Result<String> {
-    let mut output = String::new();
+    render_with(image, view, &RenderOptions::default())
+}
+
+/// Renders like `render`, with the glyphs and colors of `options`.
+pub fn render_with(image: &DynamicImage, view: &View, options: &RenderOptions) -> Result<String> {
+    write_ansi(
+        &render_cells(image, view, options)?,
+        view.target_width as usize,
+    )
+}
+
+/// Renders a view to `view.target_width` x `view.target_height` cells, row by row.
+pub fn render_cells(
+    image: &DynamicImage,
+    view: &View,
+    options: &RenderOptions,
+) -> Result<Vec<Cell>> {
+    match options.glyphs {
+        Glyphs::HalfBlock => Ok(half_block_cells(image, view, options)),
+        Glyphs::Braille => Ok(braille_cells(image, view, options)),
+        Glyphs::Quadrant | Glyphs::Sextant | Glyphs::Octant => {
+            Ok(block_cells(image, view, options))
+        }
+    }
+}
 
-    // Calculate the number of source pixels that correspond to one terminal character cell.
-    // Use floating point for precision to avoid cumulative errors.
-    let x_ratio = view.source_width as f32 / view.target_width as f32;
-    let y_ratio = view.source_height as f32 / view.target_height as f32;
+fn half_block_cells(image: &DynamicImage, view: &View, options: &RenderOptions) -> Vec<Cell> {
+    // Sample every half block first, dithering needs the neighbouring pixels
+    let columns = view.target_width as usize;
+    let pixels = sample_grid(
+        image,
+        view,
+        view.target_width,
+        view.target_height * 2,
+        options.filter,
+        options.averaging,
+        options.background,
+    );
+    let colors = quantize(&pixels, columns, options.color_depth, options.dither);
 
-    // Since each character cell represents two vertical pixels (top and bottom half),
-    // the height of the pixel block for one half is half the y_ratio.
-    // However, the loop iterates `target_height` times, and each iteration handles a full character,
-    // which covers `y_ratio` pixels in height. The logic inside handles the two halves.
-    let top_block_height = (y_ratio / 2.0).round().max(1.0) as u32;
-    let bottom_block_height = top_block_height; // Keep it simple for now
-    let block_width = x_ratio.round().max(1.0) as u32;
+    colors
+        .chunks(columns * 2)
+        .flat_map(|row| {
+            let (top, bottom) = row.split_at(columns);
+            top.iter()
+                .zip(bottom)
+                .map(|(&top, &bottom)| Cell::new('▄', Some(bottom), Some(top)))
+        })
+        .collect()
+}
 
-    for y in 0..view.target_height {
-        for x in 0..view.target_width {
-            // Calculate the source pixel coordinates for the top half-block
-            let source_pixel_x = view.source_x + (x as f32 * x_ratio) as u32;
-            let source_pixel_y_top = view.source_y + (y as f32 * y_ratio) as u32;
+/// Renders with glyphs that show several pixels per cell, in two colors each.
+fn block_cells(image: &DynamicImage, view: &View, options: &RenderOptions) -> Vec<Cell> {
+    let (cell_columns, cell_rows) = options.glyphs.cell_pixels();
 
-            let top = get_average_rgb(
-                image,
-                source_pixel_x,
-                source_pixel_y_top,
-                block_width,
-                top_block_height,
-            );
+    let width = (view.target_width * cell_columns) as usize;
+    let pixels = sample_grid(
+        image,
+        view,
+        view.target_width * cell_columns,
+        view.target_height * cell_rows,
+        options.filter,
+        options.averaging,
+        options.background,
+    );
 
-            // Calculate the source pixel coordinates for the bottom half-block
-            let source_pixel_y_bot = source_pixel_y_top + top_block_height;
+    // Dither the pixels before they are split into cells, so the error spreads to the
+    // pixels next to it and the glyph patterns draw the mix of palette colors
+    let pixels: Vec<[u8; 3]> = quantize(&pixels, width, options.color_depth, options.dither)
+        .into_iter()
+        .map(TerminalColor::rgb)
+        .collect();
 
-            let bot = get_average_rgb(
-                image,
-                source_pixel_x,
-                source_pixel_y_bot,
-                block_width,
-                bottom_block_height,
+    let columns = view.target_width as usize;
+    let mut cells = Vec::with_capacity(columns * view.target_height as usize);
+    let mut cell = Vec::with_capacity((cell_columns * cell_rows) as usize);
+    for y in 0..view.target_height as usize {
+        for x in 0..columns {
+            cell.clear();
+            for sub_y in 0..cell_rows as usize {
+                let start = (y * cell_rows as usize + sub_y) * width + x * cell_columns as usize;
+                cell.extend_from_slice(&pixels[start..start + cell_columns as usize]);
+            }
+            // A cell with more than two palette colors averages them, which needs a palette color again
+            let (pattern, foreground, background) = fit_two_colors(&cell);
+            let colors = quantize(
+                &[foreground, background],
+                2,
+                options.color_depth,
+                Dither::None,
             );
-
-            write!(
-                output,
-                "\x1b[48;2;{};{};{}m\x1b[38;2;{};{};{}m▄",
-                top[0], top[1], top[2], bot[0], bot[1], bot[2]
-            )?;
+            cells.push(Cell::new(
+                glyph(options.glyphs, pattern),
+                Some(colors[0]),
+                Some(colors[1]),
+            ));
         }
-        output.push_str("\x1b[0m\n");
     }
-    Ok(output)
+    cells
 }
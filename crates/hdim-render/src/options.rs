@@ -0,0 +1,192 @@
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit colors, `\x1b[48;2;r;g;bm`.
+    #[default]
+    TrueColor,
+    /// The xterm 256 color palette, using the 6x6x6 cube and the grayscale ramp.
+    Ansi256,
+    /// The 16 standard ANSI colors.
+    Ansi16,
+}
+
+/// How colors that are not in the palette are approximated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Dither {
+    /// Every pixel gets the nearest palette color.
+    #[default]
+    None,
+    /// Floyd-Steinberg error diffusion.
+    FloydSteinberg,
+    /// Ordered dithering with a 4x4 Bayer matrix. Stable while panning, unlike error diffusion.
+    Ordered,
+}
+
+/// How source pixels are combined into the pixels of a cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    /// The exact average of the covered area. Blocky when zoomed in.
+    #[default]
+    Box,
+    /// Lanczos with three lobes, sharp when zoomed in but may ring at hard edges.
+    Lanczos3,
+    /// Mitchell-Netravali cubic, smooth when zoomed in.
+    Mitchell,
+}
+
+impl Filter {
+    /// How far from a sample center the kernel reaches, in source pixels.
+    pub fn radius(self) -> f32 {
+        match self {
+            Self::Box => 0.5,
+            Self::Lanczos3 => 3.0,
+            Self::Mitchell => 2.0,
+        }
+    }
+
+    /// The kernel weight of a source pixel `x` pixels from the sample center.
+    pub fn weight(self, x: f32) -> f32 {
+        match self {
+            Self::Box => f32::from(x.abs() <= 0.5),
+            Self::Lanczos3 => lanczos3(x),
+            Self::Mitchell => mitchell(x),
+        }
+    }
+}
+
+fn lanczos3(x: f32) -> f32 {
+    fn sinc(x: f32) -> f32 {
+        if x == 0.0 {
+            1.0
+        } else {
+            let x = x * std::f32::consts::PI;
+            x.sin() / x
+        }
+    }
+    if x.abs() < 3.0 {
+        sinc(x) * sinc(x / 3.0)
+    } else {
+        0.0
+    }
+}
+
+/// The cubic with B = C = 1/3.
+fn mitchell(x: f32) -> f32 {
+    let x = x.abs();
+    if x < 1.0 {
+        (7.0 * x.powi(3) - 12.0 * x.powi(2) + 16.0 / 3.0) / 6.0
+    } else if x < 2.0 {
+        (-7.0 / 3.0 * x.powi(3) + 12.0 * x.powi(2) - 20.0 * x + 32.0 / 3.0) / 6.0
+    } else {
+        0.0
+    }
+}
+
+/// The color space pixels are averaged in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// The raw sRGB values. Fast, but fine bright detail comes out too dark.
+    Srgb,
+    /// Linear light, physically correct mixing.
+    #[default]
+    Linear,
+    /// Oklab, mixes like perceived lightness and hue.
+    Oklab,
+}
+
+/// What shows through transparent pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Background {
+    /// A solid sRGB color, usually the terminal's own background.
+    Color([u8; 3]),
+    /// Light and dark gray squares, each two cells wide and one cell tall.
+    Checkerboard,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self::Color([0, 0, 0])
+    }
+}
+
+impl Background {
+    /// The light and dark squares of `Checkerboard`.
+    pub const CHECKER: [[u8; 3]; 2] = [[204, 204, 204], [153, 153, 153]];
+}
+
+/// The block characters a cell is drawn with, and so how many pixels it shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Glyphs {
+    /// `▄` with two colors, 1x2 pixels per cell.
+    #[default]
+    HalfBlock,
+    /// Quadrant blocks like `▚`, 2x2 pixels per cell.
+    Quadrant,
+    /// Sextant blocks from Symbols for Legacy Computing, 2x3 pixels per cell.
+    Sextant,
+    /// Octant blocks from Symbols for Legacy Computing Supplement (Unicode 16), 2x4 pixels per cell.
+    Octant,
+    /// Braille dots, 2x4 pixels per cell in a single color. See `BrailleOptions`.
+    Braille,
+}
+
+impl Glyphs {
+    /// The number of pixels a cell shows, as (columns, rows).
+    pub fn cell_pixels(self) -> (u32, u32) {
+        match self {
+            Self::HalfBlock => (1, 2),
+            Self::Quadrant => (2, 2),
+            Self::Sextant => (2, 3),
+            Self::Octant => (2, 4),
+            Self::Braille => (2, 4),
+        }
+    }
+}
+
+/// Options for the block character renderer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub glyphs: Glyphs,
+    /// Only changes the output when zoomed in, zooming out always averages areas.
+    pub filter: Filter,
+    /// The color space pixels are averaged in.
+    pub averaging: ColorSpace,
+    /// Composited under transparent pixels.
+    pub background: Background,
+    pub color_depth: ColorDepth,
+    /// Ignored for `ColorDepth::TrueColor`.
+    pub dither: Dither,
+    /// Only used with `Glyphs::Braille`.
+    pub braille: BrailleOptions,
+}
+
+/// Decides which pixels get a braille dot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Threshold {
+    /// Pixels at least this bright get a dot.
+    Fixed(u8),
+    /// A threshold that separates the brightness histogram of the view best (Otsu's method).
+    #[default]
+    Otsu,
+    /// Floyd-Steinberg dithering of the brightness, for photos.
+    Dithered,
+}
+
+/// The color of braille dots.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DotColor {
+    /// The average color of the dotted pixels in each cell.
+    #[default]
+    Average,
+    /// One color for every dot.
+    Single([u8; 3]),
+}
+
+/// Options for `Glyphs::Braille`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BrailleOptions {
+    pub threshold: Threshold,
+    pub color: DotColor,
+    /// Put dots on the dark pixels instead, e.g. for dark lines on a white page.
+    pub invert: bool,
+}
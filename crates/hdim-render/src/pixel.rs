@@ -1,18 +1,41 @@
+use crate::options::ColorSpace;
 use image::{DynamicImage, GenericImageView};
 
-/// Calculates the average RGB color for a specific rectangular area of the image.
+/// Calculates the average RGB color for a specific rectangular area of the image, in linear light.
+///
+/// Transparent pixels count less, and the average is composited over black.
 pub fn get_average_rgb(
     image: &DynamicImage,
     start_x: u32,
     start_y: u32,
     width: u32,
     height: u32,
+) -> [u8; 3] {
+    get_average_color(
+        image,
+        start_x,
+        start_y,
+        width,
+        height,
+        ColorSpace::Linear,
+        [0, 0, 0],
+    )
+}
+
+/// Calculates the average color of a rectangular area of the image, averaged in
+/// `space` with premultiplied alpha and composited over `background`.
+pub fn get_average_color(
+    image: &DynamicImage,
+    start_x: u32,
+    start_y: u32,
+    width: u32,
+    height: u32,
+    space: ColorSpace,
+    background: [u8; 3],
 ) -> [u8; 3] {
     let (image_width, image_height) = image.dimensions();
-    let mut r_total: u64 = 0;
-    let mut g_total: u64 = 0;
-    let mut b_total: u64 = 0;
-    let mut count: u64 = 0;
+    let mut total = [0.0f32; 4];
+    let mut count: u32 = 0;
 
     // Define the boundaries of the area to iterate over, clamping to image dimensions
     let end_y = (start_y + height).min(image_height);
@@ -21,9 +44,10 @@ pub fn get_average_rgb(
     for py in start_y..end_y {
         for px in start_x..end_x {
             let pixel = image.get_pixel(px, py);
-            r_total += pixel[0] as u64;
-            g_total += pixel[1] as u64;
-            b_total += pixel[2] as u64;
+            let color = space.premultiply(pixel.0);
+            for (sum, value) in total.iter_mut().zip(color) {
+                *sum += value;
+            }
             count += 1;
         }
     }
@@ -32,9 +56,8 @@ pub fn get_average_rgb(
         return [0, 0, 0];
     }
 
-    [
-        (r_total / count) as u8,
-        (g_total / count) as u8,
-        (b_total / count) as u8,
-    ]
+    space.composite(
+        total.map(|sum| sum / count as f32),
+        space.decode(background),
+    )
 }
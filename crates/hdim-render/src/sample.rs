@@ -0,0 +1,253 @@
+use crate::options::{Background, ColorSpace, Filter};
+use crate::view::View;
+use image::{DynamicImage, GenericImageView};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::borrow::Cow;
+
+/// The source pixels that make up one output sample along an axis, and how much each counts.
+struct Weights {
+    start: u32,
+    weights: Vec<f32>,
+}
+
+/// Resamples the source rectangle of `view` to a `columns` x `rows` grid of colors, row by row.
+///
+/// Every sample averages the exact, fractional source area it covers, so no
+/// source pixel is skipped or counted twice at any zoom level. Axes that are
+/// upscaled use `filter` instead. Colors are averaged in `space` with
+/// premultiplied alpha, then composited over `background`. Parts of the view
+/// outside the image are black.
+pub fn sample_grid(
+    image: &DynamicImage,
+    view: &View,
+    columns: u32,
+    rows: u32,
+    filter: Filter,
+    space: ColorSpace,
+    background: Background,
+) -> Vec<[u8; 3]> {
+    let (image_width, image_height) = image.dimensions();
+    let horizontal = axis_weights(
+        view.source_x,
+        view.source_width,
+        columns,
+        image_width,
+        filter,
+    );
+    let vertical = axis_weights(
+        view.source_y,
+        view.source_height,
+        rows,
+        image_height,
+        filter,
+    );
+
+    if columns == 0 || rows == 0 {
+        return Vec::new();
+    }
+
+    // Filter each source row that is needed horizontally first
+    let first_row = vertical.iter().map(|w| w.start).min().unwrap_or(0);
+    let last_row = vertical
+        .iter()
+        .map(|w| w.start + w.weights.len() as u32)
+        .max()
+        .unwrap_or(0);
+    let first_column = horizontal.iter().map(|w| w.start).min().unwrap_or(0);
+    let last_column = horizontal
+        .iter()
+        .map(|w| w.start + w.weights.len() as u32)
+        .max()
+        .unwrap_or(0);
+    let region = Region::new(
+        image,
+        first_column,
+        first_row,
+        last_column.saturating_sub(first_column),
+        last_row.saturating_sub(first_row),
+    );
+
+    let filter_row = |line: &mut Vec<[f32; 4]>, y: u32| -> Vec<[f32; 4]> {
+        space.premultiply_row(region.row(y, first_column, last_column), line);
+        horizontal
+            .iter()
+            .map(|w| apply(&w.weights, &line[(w.start - first_column) as usize..]))
+            .collect()
+    };
+    #[cfg(feature = "rayon")]
+    let filtered_rows: Vec<Vec<[f32; 4]>> = (first_row..last_row)
+        .into_par_iter()
+        .map_init(Vec::new, filter_row)
+        .collect();
+    #[cfg(not(feature = "rayon"))]
+    let filtered_rows: Vec<Vec<[f32; 4]>> = {
+        let mut line = Vec::new();
+        (first_row..last_row)
+            .map(|y| filter_row(&mut line, y))
+            .collect()
+    };
+
+    // The checkerboard is fixed to the cells, so the image slides over it when panning
+    let cell_columns = (columns / view.target_width.max(1)).max(1);
+    let cell_rows = (rows / view.target_height.max(1)).max(1);
+    let backgrounds = match background {
+        Background::Color(color) => [space.decode(color); 2],
+        Background::Checkerboard => Background::CHECKER.map(|color| space.decode(color)),
+    };
+
+    // Then sum whole filtered rows, which keeps the inner loop over contiguous memory
+    let filter_column = |(y, (samples, w)): (usize, (&mut [[u8; 3]], &Weights))| {
+        if w.weights.is_empty() {
+            return;
+        }
+        let mut totals = vec![[0.0f32; 4]; columns as usize];
+        for (&weight, row) in w
+            .weights
+            .iter()
+            .zip(&filtered_rows[(w.start - first_row) as usize..])
+        {
+            for (total, pixel) in totals.iter_mut().zip(row) {
+                for c in 0..4 {
+                    total[c] += pixel[c] * weight;
+                }
+            }
+        }
+        for (x, ((sample, &total), h)) in
+            samples.iter_mut().zip(&totals).zip(&horizontal).enumerate()
+        {
+            if h.weights.is_empty() {
+                continue;
+            }
+            let square = (x as u32 / (cell_columns * 2) + y as u32 / cell_rows) % 2;
+            *sample = space.composite(total, backgrounds[square as usize]);
+        }
+    };
+    let mut samples = vec![[0, 0, 0]; (columns * rows) as usize];
+    #[cfg(feature = "rayon")]
+    samples
+        .par_chunks_mut(columns as usize)
+        .zip(vertical.par_iter())
+        .enumerate()
+        .for_each(filter_column);
+    #[cfg(not(feature = "rayon"))]
+    samples
+        .chunks_mut(columns as usize)
+        .zip(vertical.iter())
+        .enumerate()
+        .for_each(filter_column);
+    samples
+}
+
+/// Weighted sum of `pixels`. The weights are normalized, so no division is needed.
+fn apply(weights: &[f32], pixels: &[[f32; 4]]) -> [f32; 4] {
+    let mut total = [0.0; 4];
+    for (&weight, pixel) in weights.iter().zip(pixels) {
+        for c in 0..4 {
+            total[c] += pixel[c] * weight;
+        }
+    }
+    total
+}
+
+/// Computes the source pixels and weights of every output sample along one axis.
+fn axis_weights(
+    start: u32,
+    length: u32,
+    outputs: u32,
+    image_length: u32,
+    filter: Filter,
+) -> Vec<Weights> {
+    let scale = length as f32 / outputs.max(1) as f32;
+    (0..outputs)
+        .map(|i| {
+            let from = start as f32 + i as f32 * scale;
+            let to = from + scale;
+            // Box sampling, and any filter when zooming out, averages the covered area
+            let upscale = filter != Filter::Box && scale < 1.0;
+            let center = (from + to) / 2.0;
+            let (first, last) = if upscale {
+                (
+                    (center - filter.radius()).floor() as i64,
+                    (center + filter.radius()).ceil() as i64,
+                )
+            } else {
+                (from.floor() as i64, to.ceil() as i64)
+            };
+            let weight = |p: f32| {
+                if upscale {
+                    filter.weight(p + 0.5 - center)
+                } else {
+                    // How much of the pixel lies inside [from, to)
+                    (to.min(p + 1.0) - from.max(p)).max(0.0)
+                }
+            };
+
+            let first = first.clamp(0, image_length as i64);
+            let last = last.clamp(first, image_length as i64);
+            let mut weights: Vec<f32> = (first..last).map(|p| weight(p as f32)).collect();
+            let sum: f32 = weights.iter().sum();
+            if sum.abs() < f32::EPSILON {
+                weights.clear();
+            }
+            weights.iter_mut().for_each(|weight| *weight /= sum);
+            Weights {
+                start: first as u32,
+                weights,
+            }
+        })
+        .collect()
+}
+
+/// The RGBA8 pixels of the source area a grid reads, converted once up front.
+///
+/// RGBA8 images are borrowed as they are, other formats convert only the area.
+struct Region<'a> {
+    pixels: Cow<'a, [u8]>,
+    /// Bytes per row of `pixels`
+    stride: usize,
+    /// The image position of the first pixel in `pixels`
+    x: u32,
+    y: u32,
+}
+
+impl<'a> Region<'a> {
+    fn new(image: &'a DynamicImage, x: u32, y: u32, width: u32, height: u32) -> Self {
+        match image {
+            DynamicImage::ImageRgba8(buffer) => Self {
+                pixels: Cow::Borrowed(buffer.as_raw()),
+                stride: buffer.width() as usize * 4,
+                x: 0,
+                y: 0,
+            },
+            DynamicImage::ImageRgb8(buffer) => {
+                let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+                for row in y..y + height {
+                    let start = (row * buffer.width() + x) as usize * 3;
+                    let row = &buffer.as_raw()[start..start + width as usize * 3];
+                    for p in row.chunks_exact(3) {
+                        pixels.extend_from_slice(&[p[0], p[1], p[2], 255]);
+                    }
+                }
+                Self {
+                    pixels: Cow::Owned(pixels),
+                    stride: width as usize * 4,
+                    x,
+                    y,
+                }
+            }
+            _ => Self {
+                pixels: Cow::Owned(image.crop_imm(x, y, width, height).into_rgba8().into_raw()),
+                stride: width as usize * 4,
+                x,
+                y,
+            },
+        }
+    }
+
+    /// Pixels `from..to` of image row `y`.
+    fn row(&self, y: u32, from: u32, to: u32) -> &[u8] {
+        let start = (y - self.y) as usize * self.stride + (from - self.x) as usize * 4;
+        &self.pixels[start..start + (to - from) as usize * 4]
+    }
+}
@@ -0,0 +1,90 @@
+use crate::options::ColorSpace;
+use hdim_core::pyramid::linear_table;
+use palette::{FromColor, LinSrgb, Oklab, Srgb};
+use std::sync::OnceLock;
+
+/// sRGB bytes as floats, for averaging the encoded values.
+fn srgb_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| std::array::from_fn(|i| i as f32))
+}
+
+impl ColorSpace {
+    /// Converts an sRGB color into this space, where averages can be taken channel by channel.
+    pub(crate) fn decode(self, [r, g, b]: [u8; 3]) -> [f32; 3] {
+        let table = linear_table();
+        match self {
+            Self::Srgb => [r as f32, g as f32, b as f32],
+            Self::Linear => [table[r as usize], table[g as usize], table[b as usize]],
+            Self::Oklab => {
+                let linear = LinSrgb::new(table[r as usize], table[g as usize], table[b as usize]);
+                let lab = Oklab::from_color(linear);
+                [lab.l, lab.a, lab.b]
+            }
+        }
+    }
+
+    /// Converts a color of this space back to sRGB.
+    pub(crate) fn encode(self, [x, y, z]: [f32; 3]) -> [u8; 3] {
+        let linear = match self {
+            Self::Srgb => return [x, y, z].map(|c| c.round().clamp(0.0, 255.0) as u8),
+            Self::Linear => LinSrgb::new(x, y, z),
+            Self::Oklab => LinSrgb::from_color(Oklab::new(x, y, z)),
+        };
+        let linear = LinSrgb::new(
+            linear.red.clamp(0.0, 1.0),
+            linear.green.clamp(0.0, 1.0),
+            linear.blue.clamp(0.0, 1.0),
+        );
+        let srgb: Srgb<u8> = Srgb::from_linear(linear);
+        [srgb.red, srgb.green, srgb.blue]
+    }
+
+    /// Converts an sRGBA pixel into this space, with the color multiplied by its alpha.
+    pub(crate) fn premultiply(self, [r, g, b, a]: [u8; 4]) -> [f32; 4] {
+        let alpha = a as f32 / 255.0;
+        let [x, y, z] = if a == 0 {
+            [0.0; 3]
+        } else {
+            self.decode([r, g, b]).map(|c| c * alpha)
+        };
+        [x, y, z, alpha]
+    }
+
+    /// Premultiplies a row of RGBA8 pixels into `line`, like `premultiply` for every pixel.
+    pub(crate) fn premultiply_row(self, pixels: &[u8], line: &mut Vec<[f32; 4]>) {
+        line.clear();
+        let table = match self {
+            Self::Srgb => srgb_table(),
+            Self::Linear => linear_table(),
+            Self::Oklab => {
+                line.extend(
+                    pixels
+                        .chunks_exact(4)
+                        .map(|p| self.premultiply([p[0], p[1], p[2], p[3]])),
+                );
+                return;
+            }
+        };
+        // A plain table lookup per channel, without branches
+        line.extend(pixels.chunks_exact(4).map(|p| {
+            let alpha = p[3] as f32 / 255.0;
+            [
+                table[p[0] as usize] * alpha,
+                table[p[1] as usize] * alpha,
+                table[p[2] as usize] * alpha,
+                alpha,
+            ]
+        }));
+    }
+
+    /// Composites a premultiplied color of this space over `under` and converts it back to sRGB.
+    pub(crate) fn composite(self, [x, y, z, alpha]: [f32; 4], under: [f32; 3]) -> [u8; 3] {
+        let rest = 1.0 - alpha.clamp(0.0, 1.0);
+        self.encode([
+            x + under[0] * rest,
+            y + under[1] * rest,
+            z + under[2] * rest,
+        ])
+    }
+}
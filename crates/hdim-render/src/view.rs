@@ -1,5 +1,8 @@
+use image::DynamicImage;
+
 /// Defines the mapping between a rectangular area of the source image
 /// and the target rendering area in the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct View {
     /// The top-left X coordinate of the view on the source image (in pixels).
     pub source_x: u32,
@@ -14,3 +17,52 @@ pub struct View {
     /// The height of the target render area (in terminal rows).
     pub target_height: u32,
 }
+
+impl View {
+    /// Creates a view of the whole image drawn into exactly `target_width` x `target_height` cells.
+    pub fn full(image: &DynamicImage, target_width: u32, target_height: u32) -> Self {
+        View {
+            source_x: 0,
+            source_y: 0,
+            source_width: image.width(),
+            source_height: image.height(),
+            target_width,
+            target_height,
+        }
+    }
+
+    /// Creates a view of the whole image that fits within `max_columns` x `max_rows` cells.
+    ///
+    /// The aspect ratio is kept, with every cell covering two vertical pixels. The result is
+    /// at least one cell in each direction.
+    pub fn fit(image_width: u32, image_height: u32, max_columns: u32, max_rows: u32) -> Self {
+        let width_ratio = max_columns as f64 / image_width.max(1) as f64;
+        let height_ratio = (max_rows as f64 * 2.0) / image_height.max(1) as f64;
+        let ratio = width_ratio.min(height_ratio);
+
+        View {
+            source_x: 0,
+            source_y: 0,
+            source_width: image_width,
+            source_height: image_height,
+            target_width: ((image_width as f64 * ratio).round() as u32)
+                .clamp(1, max_columns.max(1)),
+            target_height: ((image_height as f64 * ratio / 2.0).round() as u32)
+                .clamp(1, max_rows.max(1)),
+        }
+    }
+
+    /// The same view on a copy of the image that is `factor` times smaller, rounded to whole pixels.
+    pub fn scaled_down(&self, factor: u32) -> Self {
+        let scale = |value: u32| (value as f64 / factor.max(1) as f64).round() as u32;
+        let source_x = scale(self.source_x);
+        let source_y = scale(self.source_y);
+        View {
+            source_x,
+            source_y,
+            source_width: scale(self.source_x + self.source_width) - source_x,
+            source_height: scale(self.source_y + self.source_height) - source_y,
+            ..*self
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::cache::RenderCache;
+use crate::cells::{Cell, CellRenderer};
+use crate::color::TerminalColor;
+use crate::view::View;
+use anyhow::Result;
+use hdim_core::HdimImage;
+use image::DynamicImage;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+use std::borrow::Cow;
+
+/// A rendered view that ratatui can draw, writing glyphs and colors straight
+/// into the buffer instead of parsing ANSI strings.
+///
+/// Rendering happens in `new` so errors can be handled before drawing.
+/// Cells outside the area the widget is drawn into are clipped. Cells from a
+/// `RenderCache` are borrowed, so a redraw of the same view copies nothing.
+#[derive(Clone, Debug, Default)]
+pub struct ImageWidget<'a> {
+    cells: Cow<'a, [Cell]>,
+    columns: usize,
+}
+
+impl<'a> ImageWidget<'a> {
+    pub fn new(renderer: &dyn CellRenderer, image: &DynamicImage, view: &View) -> Result<Self> {
+        Ok(Self::from_cells(
+            renderer.render_cells(image, view)?,
+            view.target_width as usize,
+        ))
+    }
+
+    /// Renders from the pyramid of `image`, see `CellRenderer::render_pyramid`.
+    pub fn from_pyramid(
+        renderer: &dyn CellRenderer,
+        image: &HdimImage,
+        view: &View,
+    ) -> Result<Self> {
+        Ok(Self::from_cells(
+            renderer.render_pyramid(image, view)?,
+            view.target_width as usize,
+        ))
+    }
+
+    /// Renders through `cache`, see `RenderCache::render`.
+    pub fn cached(
+        cache: &'a mut RenderCache,
+        renderer: &dyn CellRenderer,
+        image: &HdimImage,
+        generation: u64,
+        view: &View,
+    ) -> Result<Self> {
+        Ok(Self::from_cells(
+            cache.render(renderer, image, generation, view)?,
+            view.target_width as usize,
+        ))
+    }
+
+    /// Wraps cells that were already rendered, `columns` per row.
+    pub fn from_cells(cells: impl Into<Cow<'a, [Cell]>>, columns: usize) -> Self {
+        Self {
+            cells: cells.into(),
+            columns,
+        }
+    }
+}
+
+impl Widget for &ImageWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        for (row, cells) in self.cells.chunks(self.columns.max(1)).enumerate() {
+            if row >= area.height as usize {
+                break;
+            }
+            for (column, cell) in cells.iter().take(area.width as usize).enumerate() {
+                let position = (area.x + column as u16, area.y + row as u16);
+                if let Some(target) = buf.cell_mut(position) {
+                    target
+                        .set_char(cell.symbol)
+                        .set_fg(cell.fg.map_or(Color::Reset, to_color))
+                        .set_bg(cell.bg.map_or(Color::Reset, to_color));
+                }
+            }
+        }
+    }
+}
+
+impl Widget for ImageWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (&self).render(area, buf);
+    }
+}
+
+fn to_color(color: TerminalColor) -> Color {
+    match color {
+        TerminalColor::Rgb([r, g, b]) => Color::Rgb(r, g, b),
+        // The named colors keep the terminal's own 16 color codes
+        TerminalColor::Indexed(index) => match index {
+            0 => Color::Black,
+            1 => Color::Red,
+            2 => Color::Green,
+            3 => Color::Yellow,
+            4 => Color::Blue,
+            5 => Color::Magenta,
+            6 => Color::Cyan,
+            7 => Color::Gray,
+            8 => Color::DarkGray,
+            9 => Color::LightRed,
+            10 => Color::LightGreen,
+            11 => Color::LightYellow,
+            12 => Color::LightBlue,
+            13 => Color::LightMagenta,
+            14 => Color::LightCyan,
+            15 => Color::White,
+            index => Color::Indexed(index),
+        },
+    }
+}
@@ -0,0 +1,104 @@
+use hdim_render::color::TerminalColor;
+use hdim_render::pixel::{get_average_color, get_average_rgb};
+use hdim_render::{Background, ColorSpace, RenderOptions, View, render_cells};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+fn whole(width: u32, height: u32, target_width: u32, target_height: u32) -> View {
+    View {
+        source_x: 0,
+        source_y: 0,
+        source_width: width,
+        source_height: height,
+        target_width,
+        target_height,
+    }
+}
+
+/// The colors of each cell as (top, bottom).
+fn halves(image: &DynamicImage, view: &View, background: Background) -> Vec<([u8; 3], [u8; 3])> {
+    let options = RenderOptions {
+        background,
+        ..Default::default()
+    };
+    let rgb = |color| match color {
+        Some(TerminalColor::Rgb(rgb)) => rgb,
+        other => panic!("expected truecolor, got {:?}", other),
+    };
+    render_cells(image, view, &options)
+        .unwrap()
+        .into_iter()
+        .map(|cell| (rgb(cell.bg), rgb(cell.fg)))
+        .collect()
+}
+
+#[test]
+fn test_transparent_pixels_show_the_background() {
+    // Fully transparent, with stray color left under the alpha
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba([255, 0, 255, 0])));
+    let view = whole(2, 2, 2, 1);
+
+    for color in [[0, 0, 0], [30, 30, 46], [255, 255, 255]] {
+        for (top, bottom) in halves(&image, &view, Background::Color(color)) {
+            assert_eq!(top, color);
+            assert_eq!(bottom, color);
+        }
+    }
+}
+
+#[test]
+fn test_averages_use_premultiplied_alpha() {
+    // An opaque white pixel next to a transparent one full of stray red
+    let mut buffer = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 0]));
+    buffer.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    buffer.put_pixel(0, 1, Rgba([255, 255, 255, 255]));
+    let image = DynamicImage::ImageRgba8(buffer);
+
+    // Half the light of white over black, without a trace of red
+    let cells = halves(&image, &whole(2, 2, 1, 1), Background::default());
+    assert_eq!(cells, vec![([188, 188, 188], [188, 188, 188])]);
+    assert_eq!(get_average_rgb(&image, 0, 0, 2, 2), [188, 188, 188]);
+
+    // Over white, the transparent half is white as well
+    assert_eq!(
+        get_average_color(&image, 0, 0, 2, 2, ColorSpace::Linear, [255, 255, 255]),
+        [255, 255, 255]
+    );
+}
+
+#[test]
+fn test_half_transparent_color_blends_with_the_background() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 2, Rgba([255, 0, 0, 128])));
+
+    let cells = halves(&image, &whole(1, 2, 1, 1), Background::Color([0, 0, 255]));
+    let (top, _) = cells[0];
+    assert!(top[0] > 180 && top[1] == 0 && top[2] > 180, "{:?}", top);
+}
+
+#[test]
+fn test_checkerboard_squares_are_two_cells_wide() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 4, Rgba([0, 0, 0, 0])));
+    let cells = halves(&image, &whole(8, 4, 8, 2), Background::Checkerboard);
+
+    let [light, dark] = Background::CHECKER;
+    let expected_rows = [
+        [light, light, dark, dark, light, light, dark, dark],
+        [dark, dark, light, light, dark, dark, light, light],
+    ];
+    for (row, expected) in cells.chunks(8).zip(expected_rows) {
+        for (&(top, bottom), color) in row.iter().zip(expected) {
+            assert_eq!(top, color);
+            assert_eq!(bottom, color);
+        }
+    }
+}
+
+#[test]
+fn test_opaque_images_ignore_the_background() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, Rgba([10, 200, 90, 255])));
+    let view = whole(4, 4, 4, 2);
+
+    assert_eq!(
+        halves(&image, &view, Background::Checkerboard),
+        halves(&image, &view, Background::Color([255, 255, 255]))
+    );
+}
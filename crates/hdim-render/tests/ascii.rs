@@ -0,0 +1,96 @@
+use hdim_render::{Ascii, AsciiOptions, Backend, View};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// A horizontal gradient from black to white, 10 pixels wide and 4 tall.
+fn gradient() -> DynamicImage {
+    let buffer = RgbaImage::from_fn(10, 4, |x, _| {
+        let value = (x * 255 / 9) as u8;
+        Rgba([value, value, value, 255])
+    });
+    DynamicImage::ImageRgba8(buffer)
+}
+
+/// A white square in the middle of black, for edges in every direction.
+fn square() -> DynamicImage {
+    let buffer = RgbaImage::from_fn(12, 24, |x, y| {
+        if (3..9).contains(&x) && (6..18).contains(&y) {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([0, 0, 0, 255])
+        }
+    });
+    DynamicImage::ImageRgba8(buffer)
+}
+
+#[test]
+fn test_ascii_render_snapshot() {
+    let image = gradient();
+    let output = Ascii::default()
+        .render(
+            &image,
+            &View::full(&image, image.width(), image.height() / 2),
+        )
+        .unwrap();
+
+    assert!(!output.contains('\x1b'));
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_ascii_custom_ramp() {
+    let image = gradient();
+    let ascii = Ascii::new(AsciiOptions {
+        ramp: "@ ".to_string(),
+        ..Default::default()
+    });
+    let output = ascii
+        .render(
+            &image,
+            &View::full(&image, image.width(), image.height() / 2),
+        )
+        .unwrap();
+
+    assert_eq!(output, "@@@@@     \n@@@@@     \n");
+}
+
+#[test]
+fn test_ascii_edges_follow_their_direction() {
+    let image = square();
+    let ascii = Ascii::new(AsciiOptions {
+        edges: true,
+        ..Default::default()
+    });
+    let output = ascii
+        .render(
+            &image,
+            &View::full(&image, image.width(), image.height() / 2),
+        )
+        .unwrap();
+
+    println!("{}", output);
+    let lines: Vec<&str> = output.lines().collect();
+    // The left and right sides of the square are vertical edges
+    assert!(lines[6].contains('|'));
+    // The top is a horizontal edge
+    assert!(lines[2].contains('-'));
+    // Far from the square there are no edges
+    assert_eq!(lines[11].trim(), "");
+}
+
+#[test]
+fn test_ascii_rejects_empty_ramp() {
+    let image = gradient();
+    let ascii = Ascii::new(AsciiOptions {
+        ramp: String::new(),
+        ..Default::default()
+    });
+
+    assert!(
+        ascii
+            .render(
+                &image,
+                &View::full(&image, image.width(), image.height() / 2)
+            )
+            .is_err()
+    );
+}
@@ -0,0 +1,105 @@
+use hdim_render::braille::otsu_threshold;
+use hdim_render::glyphs::glyph;
+use hdim_render::{BrailleOptions, DotColor, Glyphs, RenderOptions, Threshold, View, render_with};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// A white diagonal line on black, 4x8 pixels or two braille cells.
+fn diagonal() -> DynamicImage {
+    let buffer = RgbaImage::from_fn(4, 8, |x, y| {
+        if x == y / 2 {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([0, 0, 0, 255])
+        }
+    });
+    DynamicImage::ImageRgba8(buffer)
+}
+
+fn braille(braille: BrailleOptions) -> RenderOptions {
+    RenderOptions {
+        glyphs: Glyphs::Braille,
+        braille,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_braille_dot_order() {
+    assert_eq!(glyph(Glyphs::Braille, 0), '\u{2800}');
+    // Left column top to bottom, dots 1, 2, 3 and 7
+    assert_eq!(glyph(Glyphs::Braille, 0b0101_0101), '⡇');
+    // Right column, dots 4, 5, 6 and 8
+    assert_eq!(glyph(Glyphs::Braille, 0b1010_1010), '⢸');
+    assert_eq!(glyph(Glyphs::Braille, 0xff), '⣿');
+}
+
+#[test]
+fn test_braille_render_snapshot() {
+    let image = diagonal();
+    let options = braille(BrailleOptions {
+        threshold: Threshold::Fixed(128),
+        ..Default::default()
+    });
+    let output = render_with(
+        &image,
+        &View::full(&image, image.width() / 2, image.height() / 4),
+        &options,
+    )
+    .unwrap();
+
+    println!("Visual Output:\n{}", output);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_braille_single_color_and_invert() {
+    let image = diagonal();
+    let options = braille(BrailleOptions {
+        threshold: Threshold::Otsu,
+        color: DotColor::Single([0, 255, 0]),
+        invert: true,
+    });
+    let output = render_with(
+        &image,
+        &View::full(&image, image.width() / 2, image.height() / 4),
+        &options,
+    )
+    .unwrap();
+
+    // The dots are on the black pixels now, the color is set once per row
+    assert_eq!(
+        output,
+        "\x1b[38;2;0;255;0m⡜⣿\x1b[0m\n\x1b[38;2;0;255;0m⣿⡜\x1b[0m\n"
+    );
+}
+
+#[test]
+fn test_otsu_threshold_separates_classes() {
+    let mut values = vec![20u8; 50];
+    values.extend([200u8; 30]);
+
+    let threshold = otsu_threshold(&values);
+    assert!((20..200).contains(&threshold), "threshold {}", threshold);
+}
+
+#[test]
+fn test_dithered_gray_gets_half_the_dots() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(16, 16, Rgba([128, 128, 128, 255])));
+    let options = braille(BrailleOptions {
+        threshold: Threshold::Dithered,
+        ..Default::default()
+    });
+    let output = render_with(
+        &image,
+        &View::full(&image, image.width() / 2, image.height() / 4),
+        &options,
+    )
+    .unwrap();
+
+    let dots: u32 = output
+        .chars()
+        .filter(|c| ('\u{2800}'..='\u{28ff}').contains(c))
+        .map(|c| (c as u32 - 0x2800).count_ones())
+        .sum();
+    assert!((112..=144).contains(&dots), "{} of 256 dots", dots);
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use hdim_core::HdimImage;
+use hdim_render::{
+    Backend, Cell, CellRenderer, ColorDepth, Dither, HalfBlock, RenderCache, RenderOptions, View,
+};
+use image::{DynamicImage, Rgba, RgbaImage};
+use std::cell::RefCell;
+
+/// Half blocks that remember the views they rendered.
+#[derive(Default)]
+struct Counting {
+    inner: HalfBlock,
+    views: RefCell<Vec<View>>,
+}
+
+impl Backend for Counting {
+    fn render(&self, image: &DynamicImage, view: &View) -> Result<String> {
+        self.inner.render(image, view)
+    }
+}
+
+impl CellRenderer for Counting {
+    fn render_cells(&self, image: &DynamicImage, view: &View) -> Result<Vec<Cell>> {
+        self.views.borrow_mut().push(*view);
+        self.inner.render_cells(image, view)
+    }
+
+    fn cells_are_local(&self) -> bool {
+        self.inner.cells_are_local()
+    }
+}
+
+impl Counting {
+    /// The number of cells rendered since the last call.
+    fn rendered_cells(&self) -> u32 {
+        self.views
+            .borrow_mut()
+            .drain(..)
+            .map(|view| view.target_width * view.target_height)
+            .sum()
+    }
+}
+
+/// A 64x64 image without any two equal pixels in a row or column.
+fn gradient() -> HdimImage {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/images/WindowsXP.png");
+    let buffer = RgbaImage::from_fn(64, 64, |x, y| {
+        Rgba([(x * 4) as u8, (y * 4) as u8, ((x + y) * 2) as u8, 255])
+    });
+    HdimImage::from_path(path.as_ref())
+        .unwrap()
+        .with_data(DynamicImage::ImageRgba8(buffer))
+}
+
+/// One source pixel per column and two per row of cells.
+fn view_at(source_x: u32, source_y: u32) -> View {
+    View {
+        source_x,
+        source_y,
+        source_width: 20,
+        source_height: 20,
+        target_width: 20,
+        target_height: 10,
+    }
+}
+
+#[test]
+fn test_unchanged_views_are_not_rendered_again() {
+    let image = gradient();
+    let renderer = Counting::default();
+    let mut cache = RenderCache::new();
+
+    let first = cache
+        .render(&renderer, &image, 0, &view_at(4, 4))
+        .unwrap()
+        .to_vec();
+    assert_eq!(renderer.rendered_cells(), 200);
+    let second = cache.render(&renderer, &image, 0, &view_at(4, 4)).unwrap();
+
+    assert_eq!(renderer.rendered_cells(), 0);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_pans_by_whole_cells_only_render_the_exposed_cells() {
+    let image = gradient();
+    let renderer = Counting::default();
+    let mut cache = RenderCache::new();
+    cache
+        .render(&renderer, &image, 0, &view_at(10, 10))
+        .unwrap();
+    renderer.rendered_cells();
+
+    // Three columns right, then two rows up, then diagonally
+    for (view, exposed) in [
+        (view_at(13, 10), 3 * 10),
+        (view_at(13, 6), 2 * 20),
+        (view_at(11, 12), 3 * 20 + 2 * 7),
+    ] {
+        let cells = cache.render(&renderer, &image, 0, &view).unwrap().to_vec();
+        assert_eq!(renderer.rendered_cells(), exposed, "{:?}", view);
+        assert_eq!(
+            cells,
+            renderer.inner.render_cells(&image.data, &view).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_partial_cell_pans_and_new_images_render_everything() {
+    let image = gradient();
+    let renderer = Counting::default();
+    let mut cache = RenderCache::new();
+    cache
+        .render(&renderer, &image, 0, &view_at(10, 10))
+        .unwrap();
+    renderer.rendered_cells();
+
+    // Half a row
+    cache
+        .render(&renderer, &image, 0, &view_at(10, 11))
+        .unwrap();
+    assert_eq!(renderer.rendered_cells(), 200);
+    cache
+        .render(&renderer, &image, 1, &view_at(10, 11))
+        .unwrap();
+    assert_eq!(renderer.rendered_cells(), 200);
+}
+
+#[test]
+fn test_dithered_cells_are_not_shifted() {
+    let image = gradient();
+    let renderer = Counting {
+        inner: HalfBlock::new(RenderOptions {
+            color_depth: ColorDepth::Ansi16,
+            dither: Dither::FloydSteinberg,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut cache = RenderCache::new();
+    cache
+        .render(&renderer, &image, 0, &view_at(10, 10))
+        .unwrap();
+    renderer.rendered_cells();
+
+    cache
+        .render(&renderer, &image, 0, &view_at(13, 10))
+        .unwrap();
+    assert_eq!(renderer.rendered_cells(), 200);
+}
@@ -0,0 +1,102 @@
+use hdim_render::color::{TerminalColor, nearest_16, nearest_256, palette_rgb, quantize};
+use hdim_render::{ColorDepth, Dither, RenderOptions, View, render_with};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+fn test_image() -> DynamicImage {
+    let mut buffer = RgbaImage::new(2, 2);
+    buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255])); // Red
+    buffer.put_pixel(1, 0, Rgba([0, 0, 255, 255])); // Blue
+    buffer.put_pixel(0, 1, Rgba([0, 255, 0, 255])); // Green
+    buffer.put_pixel(1, 1, Rgba([255, 255, 255, 255])); // White
+    DynamicImage::ImageRgba8(buffer)
+}
+
+#[test]
+fn test_nearest_256_matches_palette_entries() {
+    for index in 16..=255 {
+        assert_eq!(nearest_256(palette_rgb(index)), index, "index {}", index);
+    }
+}
+
+#[test]
+fn test_nearest_256_prefers_grayscale_ramp_for_grays() {
+    assert_eq!(nearest_256([128, 128, 128]), 244);
+    assert_eq!(nearest_256([0, 0, 0]), 16);
+    assert_eq!(nearest_256([255, 255, 255]), 231);
+}
+
+#[test]
+fn test_nearest_16() {
+    assert_eq!(nearest_16([250, 10, 10]), 9);
+    assert_eq!(nearest_16([200, 0, 0]), 1);
+    assert_eq!(nearest_16([20, 20, 20]), 0);
+    assert_eq!(nearest_16([250, 250, 250]), 15);
+}
+
+#[test]
+fn test_render_ansi256_snapshot() {
+    let options = RenderOptions {
+        color_depth: ColorDepth::Ansi256,
+        dither: Dither::None,
+        ..Default::default()
+    };
+    let output = render_with(&test_image(), &View::full(&test_image(), 2, 1), &options).unwrap();
+
+    assert!(!output.contains(";2;"));
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_render_ansi16_uses_basic_codes() {
+    let options = RenderOptions {
+        color_depth: ColorDepth::Ansi16,
+        dither: Dither::None,
+        ..Default::default()
+    };
+    let output = render_with(&test_image(), &View::full(&test_image(), 2, 1), &options).unwrap();
+
+    // Bright red over bright green, then blue over white
+    assert_eq!(output, "\x1b[101m\x1b[92m▄\x1b[44m\x1b[97m▄\x1b[0m\n");
+}
+
+#[test]
+fn test_truecolor_options_match_render() {
+    let image = test_image();
+    let output = render_with(&image, &View::full(&image, 2, 1), &RenderOptions::default()).unwrap();
+
+    assert_eq!(
+        output,
+        hdim_render::render(&image, &View::full(&image, 2, 1)).unwrap()
+    );
+}
+
+/// A flat color between two palette entries should come out as a mix of both.
+fn dithered_mix(dither: Dither) -> (usize, usize) {
+    let pixels = vec![[128, 0, 0]; 64];
+    let colors = quantize(&pixels, 8, ColorDepth::Ansi16, dither);
+    let sgr = |color: &TerminalColor| {
+        let mut output = String::new();
+        color.write_sgr(&mut output, false).unwrap();
+        output
+    };
+    let dark = colors.iter().filter(|c| sgr(c) == "30").count();
+    let red = colors.iter().filter(|c| sgr(c) == "31").count();
+    (dark, red)
+}
+
+#[test]
+fn test_dithering_mixes_neighbouring_colors() {
+    let (dark, red) = dithered_mix(Dither::None);
+    assert!(dark == 64 || red == 64);
+
+    for dither in [Dither::FloydSteinberg, Dither::Ordered] {
+        let (dark, red) = dithered_mix(dither);
+        assert!(
+            dark > 8 && red > 8,
+            "{:?}: {} dark, {} red",
+            dither,
+            dark,
+            red
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use hdim_render::color::TerminalColor;
+use hdim_render::pixel::{get_average_color, get_average_rgb};
+use hdim_render::sample::sample_grid;
+use hdim_render::{Background, ColorSpace, Filter, RenderOptions, View, render_cells};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// An 8x8 checkerboard of single pixels in two colors.
+fn checkerboard(a: [u8; 3], b: [u8; 3]) -> DynamicImage {
+    let buffer = RgbaImage::from_fn(8, 8, |x, y| {
+        let [r, g, b] = if (x + y) % 2 == 0 { a } else { b };
+        Rgba([r, g, b, 255])
+    });
+    DynamicImage::ImageRgba8(buffer)
+}
+
+fn whole(target_width: u32, target_height: u32) -> View {
+    View {
+        source_x: 0,
+        source_y: 0,
+        source_width: 8,
+        source_height: 8,
+        target_width,
+        target_height,
+    }
+}
+
+fn average(image: &DynamicImage, space: ColorSpace) -> [u8; 3] {
+    sample_grid(
+        image,
+        &whole(1, 1),
+        1,
+        1,
+        Filter::Box,
+        space,
+        Background::default(),
+    )[0]
+}
+
+fn assert_near(actual: [u8; 3], expected: [u8; 3]) {
+    assert!(
+        actual
+            .iter()
+            .zip(expected)
+            .all(|(&a, e)| a.abs_diff(e) <= 1),
+        "got {:?}, expected {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_black_and_white_mix_per_color_space() {
+    let image = checkerboard([0, 0, 0], [255, 255, 255]);
+
+    // Half the light, sRGB encoded
+    assert_near(average(&image, ColorSpace::Linear), [188, 188, 188]);
+    // The classic, too dark, average of the encoded values
+    assert_near(average(&image, ColorSpace::Srgb), [128, 128, 128]);
+    // Halfway in perceived lightness
+    assert_near(average(&image, ColorSpace::Oklab), [99, 99, 99]);
+}
+
+#[test]
+fn test_red_and_green_mix_to_bright_yellow_in_linear_light() {
+    let image = checkerboard([255, 0, 0], [0, 255, 0]);
+
+    assert_near(average(&image, ColorSpace::Linear), [188, 188, 0]);
+    assert_near(average(&image, ColorSpace::Srgb), [128, 128, 0]);
+
+    let [r, g, b] = average(&image, ColorSpace::Oklab);
+    assert!(r > 128 && g > 128 && b < 64, "{:?}", [r, g, b]);
+}
+
+#[test]
+fn test_render_options_select_the_color_space() {
+    let image = checkerboard([0, 0, 0], [255, 255, 255]);
+    for (space, expected) in [
+        (ColorSpace::Linear, [188, 188, 188]),
+        (ColorSpace::Srgb, [128, 128, 128]),
+    ] {
+        let options = RenderOptions {
+            averaging: space,
+            ..Default::default()
+        };
+        let cells = render_cells(&image, &whole(1, 1), &options).unwrap();
+        for color in [cells[0].fg, cells[0].bg] {
+            match color {
+                Some(TerminalColor::Rgb(rgb)) => assert_near(rgb, expected),
+                other => panic!("expected truecolor, got {:?}", other),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_get_average_rgb_uses_linear_light() {
+    let image = checkerboard([0, 0, 0], [255, 255, 255]);
+
+    assert_near(get_average_rgb(&image, 0, 0, 2, 2), [188, 188, 188]);
+    assert_near(
+        get_average_color(&image, 0, 0, 2, 2, ColorSpace::Srgb, [0, 0, 0]),
+        [128, 128, 128],
+    );
+}
@@ -1,4 +1,4 @@
-use hdim_render::{View, render};
+use hdim_render::{Backend, Sixel, View, render};
 use image::{GenericImageView, Rgba, RgbaImage};
 
 #[test]
@@ -28,6 +28,33 @@ fn test_complex_render() {
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_complex_render_sixel() {
+    let image = image::DynamicImage::ImageRgba8(generate_benchmark_image());
+
+    // 32x16 cells of 2x4 pixels show the 256x256 image at a quarter of its size
+    let (image_width, image_height) = image.dimensions();
+    let view = View {
+        source_x: 0,
+        source_y: 0,
+        source_width: image_width,
+        source_height: image_height,
+        target_width: 32,
+        target_height: 16,
+    };
+    let sixel = Sixel {
+        colors: 16,
+        cell_width: 2,
+        cell_height: 4,
+    };
+
+    // Run System Under Test
+    let output = sixel.render(&image, &view).unwrap();
+
+    // Verify with snapshot
+    insta::assert_snapshot!(output);
+}
+
 fn generate_benchmark_image() -> RgbaImage {
     let size = 256;
     let mut image = RgbaImage::new(size, size);
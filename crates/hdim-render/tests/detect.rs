@@ -0,0 +1,139 @@
+use hdim_render::detect::{
+    BackendKind, Environment, Replies, choose, is_complete, parse_background, parse_replies,
+};
+
+fn env(term: &str, colorterm: &str, term_program: &str) -> Environment {
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+    Environment {
+        colorterm: non_empty(colorterm),
+        term: non_empty(term),
+        term_program: non_empty(term_program),
+        tmux: false,
+    }
+}
+
+// Replies as sent by real terminals to `detect::QUERIES`
+const KITTY_REPLIES: &str = "\x1b_Gi=31;OK\x1b\\\x1bP1+r524742=382F382F38\x1b\\\x1b[?62;c";
+const FOOT_REPLIES: &str = "\x1bP0+r524742\x1b\\\x1bP1+r5463\x1b\\\x1b[?62;4;22c";
+const XTERM_REPLIES: &str = "\x1bP0+r524742\x1b\\\x1bP0+r5463\x1b\\\x1b[?64;1;2;6;9;15;18;21;22c";
+const LINUX_CONSOLE_REPLIES: &str = "\x1b[?6c";
+
+#[test]
+fn test_parse_kitty_replies() {
+    assert_eq!(
+        parse_replies(KITTY_REPLIES),
+        Replies {
+            kitty: true,
+            sixel: false,
+            truecolor: true,
+            answered: true,
+        }
+    );
+}
+
+#[test]
+fn test_parse_sixel_replies() {
+    let replies = parse_replies(FOOT_REPLIES);
+
+    assert!(replies.sixel);
+    assert!(replies.truecolor);
+    assert!(!replies.kitty);
+}
+
+#[test]
+fn test_parse_replies_without_graphics() {
+    let replies = parse_replies(XTERM_REPLIES);
+
+    assert_eq!(
+        replies,
+        Replies {
+            kitty: false,
+            sixel: false,
+            truecolor: false,
+            answered: true,
+        }
+    );
+}
+
+#[test]
+fn test_replies_are_complete_after_da1() {
+    assert!(is_complete(LINUX_CONSOLE_REPLIES));
+    assert!(is_complete(KITTY_REPLIES));
+    assert!(!is_complete("\x1b_Gi=31;OK\x1b\\"));
+    assert!(!is_complete("\x1b[?62;4"));
+}
+
+#[test]
+fn test_choose_prefers_graphics_in_order() {
+    let plain = env("xterm-256color", "truecolor", "");
+    let kitty_and_sixel = Replies {
+        kitty: true,
+        sixel: true,
+        ..Replies::default()
+    };
+    let sixel = parse_replies(FOOT_REPLIES);
+
+    assert_eq!(choose(&plain, Some(&kitty_and_sixel)), BackendKind::Kitty);
+    assert_eq!(choose(&plain, Some(&sixel)), BackendKind::Sixel);
+    assert_eq!(
+        choose(&env("xterm-256color", "truecolor", "iTerm.app"), None),
+        BackendKind::Iterm
+    );
+}
+
+#[test]
+fn test_choose_from_environment_only() {
+    assert_eq!(
+        choose(&env("xterm-kitty", "", ""), None),
+        BackendKind::Kitty
+    );
+    assert_eq!(choose(&env("foot", "", ""), None), BackendKind::Sixel);
+    assert_eq!(
+        choose(&env("xterm-256color", "24bit", ""), None),
+        BackendKind::TrueColor
+    );
+    assert_eq!(
+        choose(&env("xterm-256color", "", ""), None),
+        BackendKind::Ansi256
+    );
+    assert_eq!(choose(&env("linux", "", ""), None), BackendKind::Ansi16);
+    assert_eq!(choose(&env("", "", ""), None), BackendKind::Ansi16);
+}
+
+#[test]
+fn test_choose_truecolor_from_xtgettcap() {
+    let replies = Replies {
+        truecolor: true,
+        answered: true,
+        ..Replies::default()
+    };
+
+    assert_eq!(
+        choose(&env("screen", "", ""), Some(&replies)),
+        BackendKind::TrueColor
+    );
+}
+
+#[test]
+fn test_choose_skips_graphics_in_tmux() {
+    let mut tmux = env("tmux-256color", "truecolor", "");
+    tmux.tmux = true;
+    let replies = parse_replies(KITTY_REPLIES);
+
+    assert_eq!(choose(&tmux, Some(&replies)), BackendKind::TrueColor);
+}
+
+#[test]
+fn test_parse_background_reply() {
+    assert_eq!(
+        parse_background("\x1b]11;rgb:ffff/8080/0000\x1b\\\x1b[?62;c"),
+        Some([255, 128, 0])
+    );
+    // Some terminals answer with two digits per channel and end with BEL
+    assert_eq!(
+        parse_background("\x1b]11;rgb:1e/1e/2e\x07\x1b[?62;c"),
+        Some([30, 30, 46])
+    );
+    assert_eq!(parse_background(LINUX_CONSOLE_REPLIES), None);
+    assert_eq!(parse_background("\x1b]11;rgb:ffff/ffff\x07"), None);
+}
@@ -0,0 +1,142 @@
+use hdim_render::glyphs::{fit_two_colors, glyph};
+use hdim_render::{ColorDepth, Dither, Glyphs, RenderOptions, View, render_cells, render_with};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+#[test]
+fn test_quadrant_glyphs() {
+    assert_eq!(glyph(Glyphs::Quadrant, 0b0000), ' ');
+    assert_eq!(glyph(Glyphs::Quadrant, 0b1001), '▚');
+    assert_eq!(glyph(Glyphs::Quadrant, 0b0110), '▞');
+    assert_eq!(glyph(Glyphs::Quadrant, 0b1100), '▄');
+    assert_eq!(glyph(Glyphs::Quadrant, 0b1111), '█');
+}
+
+#[test]
+fn test_sextant_glyphs() {
+    assert_eq!(glyph(Glyphs::Sextant, 0b000001), '\u{1FB00}');
+    assert_eq!(glyph(Glyphs::Sextant, 0b010100), '\u{1FB13}');
+    assert_eq!(glyph(Glyphs::Sextant, 0b010101), '▌');
+    assert_eq!(glyph(Glyphs::Sextant, 0b010110), '\u{1FB14}');
+    assert_eq!(glyph(Glyphs::Sextant, 0b101010), '▐');
+    assert_eq!(glyph(Glyphs::Sextant, 0b111110), '\u{1FB3B}');
+    assert_eq!(glyph(Glyphs::Sextant, 0b111111), '█');
+}
+
+#[test]
+fn test_octant_glyphs() {
+    assert_eq!(glyph(Glyphs::Octant, 0b00000000), ' ');
+    assert_eq!(glyph(Glyphs::Octant, 0b00000100), '\u{1CD00}');
+    assert_eq!(glyph(Glyphs::Octant, 0b00000101), '▘');
+    assert_eq!(glyph(Glyphs::Octant, 0b00000110), '\u{1CD01}');
+    assert_eq!(glyph(Glyphs::Octant, 0b00010100), '\u{1FBE6}');
+    assert_eq!(glyph(Glyphs::Octant, 0b01010101), '▌');
+    assert_eq!(glyph(Glyphs::Octant, 0b11111110), '\u{1CDE5}');
+    assert_eq!(glyph(Glyphs::Octant, 0b11111111), '█');
+}
+
+#[test]
+fn test_fit_two_colors_splits_checkerboard() {
+    let black = [0, 0, 0];
+    let white = [255, 255, 255];
+    let (pattern, foreground, background) = fit_two_colors(&[white, black, black, white]);
+
+    // The last pixel is always background, so black is drawn in the foreground
+    assert_eq!(pattern, 0b0110);
+    assert_eq!((foreground, background), (black, white));
+}
+
+#[test]
+fn test_fit_two_colors_of_flat_cell() {
+    let gray = [128, 128, 128];
+    let (pattern, _, background) = fit_two_colors(&[gray; 6]);
+
+    assert_eq!(pattern, 0);
+    assert_eq!(background, gray);
+}
+
+fn checkerboard(size: u32) -> DynamicImage {
+    let buffer = RgbaImage::from_fn(size, size, |x, y| {
+        if (x + y) % 2 == 0 {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([0, 0, 0, 255])
+        }
+    });
+    DynamicImage::ImageRgba8(buffer)
+}
+
+#[test]
+fn test_quadrant_render_shows_pixel_detail() {
+    let view = View {
+        source_x: 0,
+        source_y: 0,
+        source_width: 4,
+        source_height: 4,
+        target_width: 2,
+        target_height: 2,
+    };
+    let options = RenderOptions {
+        glyphs: Glyphs::Quadrant,
+        ..Default::default()
+    };
+    let output = render_with(&checkerboard(4), &view, &options).unwrap();
+
+    // Half blocks would average every cell to gray
+    assert_eq!(output.matches('▞').count(), 4);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_sextant_render_size() {
+    let image = checkerboard(60);
+    let view = View::fit(60, 60, 10, 10);
+    let options = RenderOptions {
+        glyphs: Glyphs::Sextant,
+        ..Default::default()
+    };
+    let output = render_with(&image, &view, &options).unwrap();
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), view.target_height as usize);
+    assert!(lines.iter().all(|line| line.ends_with("\x1b[0m")));
+}
+
+#[test]
+fn test_octant_render_size() {
+    let image = checkerboard(80);
+    let view = View::fit(80, 80, 10, 10);
+    let options = RenderOptions {
+        glyphs: Glyphs::Octant,
+        ..Default::default()
+    };
+    let output = render_with(&image, &view, &options).unwrap();
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), view.target_height as usize);
+    assert!(lines.iter().all(|line| line.ends_with("\x1b[0m")));
+}
+
+#[test]
+fn test_quadrant_dithering_draws_the_mix_inside_cells() {
+    // Between black and the dark gray of the 16 colors
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(16, 16, Rgba([64, 64, 64, 255])));
+    let view = View::fit(16, 16, 8, 8);
+    let options = RenderOptions {
+        glyphs: Glyphs::Quadrant,
+        color_depth: ColorDepth::Ansi16,
+        dither: Dither::FloydSteinberg,
+        ..Default::default()
+    };
+    let cells = render_cells(&image, &view, &options).unwrap();
+
+    let mixed = cells
+        .iter()
+        .filter(|cell| cell.fg != cell.bg && !matches!(cell.symbol, ' ' | '█'))
+        .count();
+    assert!(
+        mixed > cells.len() / 2,
+        "{} of {} cells",
+        mixed,
+        cells.len()
+    );
+}
@@ -0,0 +1,84 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use hdim_render::{Backend, Iterm, View};
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+fn make_grid_image() -> DynamicImage {
+    let mut buffer = RgbaImage::new(2, 2);
+    buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255])); // Red
+    buffer.put_pixel(1, 0, Rgba([0, 0, 255, 255])); // Blue
+    buffer.put_pixel(0, 1, Rgba([0, 255, 0, 255])); // Green
+    buffer.put_pixel(1, 1, Rgba([255, 255, 255, 255])); // White
+    DynamicImage::ImageRgba8(buffer)
+}
+
+/// Splits an OSC 1337 sequence into its arguments and decoded payload.
+fn parse(output: &str) -> (String, Vec<u8>) {
+    let body = output
+        .strip_prefix("\x1b]1337;File=")
+        .and_then(|body| body.strip_suffix('\x07'))
+        .expect("not an OSC 1337 File sequence");
+    let (arguments, payload) = body.split_once(':').unwrap();
+    (arguments.to_string(), STANDARD.decode(payload).unwrap())
+}
+
+#[test]
+fn test_iterm_render_snapshot() {
+    let image = make_grid_image();
+    let view = View {
+        source_x: 0,
+        source_y: 0,
+        source_width: 2,
+        source_height: 2,
+        target_width: 2,
+        target_height: 1,
+    };
+
+    let output = Iterm.render(&image, &view).expect("Rendering failed");
+
+    println!("Escaped String: {:?}", output);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_iterm_payload_is_the_view_as_png() {
+    let image = make_grid_image();
+    let view = View {
+        source_x: 0,
+        source_y: 1,
+        source_width: 2,
+        source_height: 1,
+        target_width: 6,
+        target_height: 3,
+    };
+
+    let (arguments, png) = parse(&Iterm.render(&image, &view).unwrap());
+    let decoded = image::load_from_memory(&png).unwrap();
+
+    assert_eq!(
+        arguments,
+        format!(
+            "inline=1;size={};width=6;height=3;preserveAspectRatio=0",
+            png.len()
+        )
+    );
+    assert_eq!(decoded.dimensions(), (2, 1));
+    assert_eq!(decoded.get_pixel(0, 0), Rgba([0, 255, 0, 255]));
+    assert_eq!(decoded.get_pixel(1, 0), Rgba([255, 255, 255, 255]));
+}
+
+#[test]
+fn test_iterm_shrinks_cells_past_image_edge() {
+    let image = make_grid_image();
+    let view = View {
+        source_x: 0,
+        source_y: 0,
+        source_width: 2,
+        source_height: 8,
+        target_width: 4,
+        target_height: 4,
+    };
+
+    let (arguments, _) = parse(&Iterm.render(&image, &view).unwrap());
+
+    assert!(arguments.contains(";width=4;height=1;"));
+}
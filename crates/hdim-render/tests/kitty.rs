@@ -0,0 +1,107 @@
+use hdim_render::{Backend, HalfBlock, Kitty, View, render};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+fn make_grid_image() -> DynamicImage {
+    let mut buffer = RgbaImage::new(2, 2);
+    buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255])); // Red
+    buffer.put_pixel(1, 0, Rgba([0, 0, 255, 255])); // Blue
+    buffer.put_pixel(0, 1, Rgba([0, 255, 0, 255])); // Green
+    buffer.put_pixel(1, 1, Rgba([255, 255, 255, 255])); // White
+    DynamicImage::ImageRgba8(buffer)
+}
+
+#[test]
+fn test_kitty_render_snapshot() {
+    let image = make_grid_image();
+    let view = View::full(&image, 2, 1);
+
+    let output = Kitty::new(7)
+        .render(&image, &view)
+        .expect("Rendering failed");
+
+    println!("Escaped String: {:?}", output);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_kitty_sends_only_the_view() {
+    let image = make_grid_image();
+    let view = View {
+        source_x: 1,
+        source_y: 0,
+        source_width: 1,
+        source_height: 2,
+        target_width: 1,
+        target_height: 1,
+    };
+
+    let output = Kitty::default().render(&image, &view).unwrap();
+
+    // Blue above white, as raw RGBA: 0000ffff ffffffff
+    assert!(output.starts_with("\x1b_Ga=T,f=32,s=1,v=2,i=1,c=1,r=1,q=2,m=0;AAD///////8=\x1b\\"));
+}
+
+#[test]
+fn test_kitty_chunks_large_payloads() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, Rgba([1, 2, 3, 255])));
+    let view = View::full(&image, 32, 16);
+
+    let output = Kitty::default().render(&image, &view).unwrap();
+    let chunks: Vec<&str> = output
+        .split("\x1b\\")
+        .filter(|chunk| !chunk.is_empty())
+        .collect();
+
+    // 64 * 64 * 4 bytes are 21848 base64 characters, so six chunks
+    assert_eq!(chunks.len(), 6);
+    assert!(chunks[0].contains("m=1;"));
+    assert!(
+        chunks[1..5]
+            .iter()
+            .all(|chunk| chunk.starts_with("\x1b_Gm=1;"))
+    );
+    assert!(chunks[5].starts_with("\x1b_Gm=0;"));
+    for chunk in &chunks {
+        let payload = chunk.split_once(';').unwrap().1;
+        assert!(payload.len() <= 4096);
+    }
+}
+
+#[test]
+fn test_kitty_shrinks_placement_past_image_edge() {
+    let image = make_grid_image();
+    // The view is twice as wide as the image, so only half the columns are used
+    let view = View {
+        source_x: 0,
+        source_y: 0,
+        source_width: 4,
+        source_height: 2,
+        target_width: 8,
+        target_height: 2,
+    };
+
+    let output = Kitty::default().render(&image, &view).unwrap();
+
+    assert!(output.starts_with("\x1b_Ga=T,f=32,s=2,v=2,i=1,c=4,r=2,"));
+}
+
+#[test]
+fn test_kitty_place_and_delete_reuse_id() {
+    let image = make_grid_image();
+    let view = View::full(&image, 4, 2);
+    let kitty = Kitty::new(42);
+
+    assert_eq!(kitty.place(&view), "\x1b_Ga=p,i=42,c=4,r=2,q=2\x1b\\");
+    assert_eq!(kitty.delete(), "\x1b_Ga=d,d=I,i=42,q=2\x1b\\");
+}
+
+#[test]
+fn test_half_block_backend_matches_render() {
+    let image = make_grid_image();
+    let view = View::full(&image, 2, 1);
+
+    assert_eq!(
+        HalfBlock::default().render(&image, &view).unwrap(),
+        render(&image, &view).unwrap()
+    );
+}
@@ -0,0 +1,76 @@
+use hdim_core::HdimImage;
+use hdim_render::color::TerminalColor;
+use hdim_render::{Ascii, Cell, CellRenderer, Glyphs, HalfBlock, RenderOptions, View};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+fn photo() -> HdimImage {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/images/WindowsXP.png");
+    HdimImage::from_path(path.as_ref()).unwrap()
+}
+
+/// The mean difference of the color channels of two renderings.
+fn mean_difference(a: &[Cell], b: &[Cell]) -> f32 {
+    let channels = |cell: &Cell| {
+        [cell.fg, cell.bg].map(|color| match color {
+            Some(TerminalColor::Rgb(rgb)) => rgb,
+            _ => [0, 0, 0],
+        })
+    };
+    a.iter()
+        .zip(b)
+        .flat_map(|(a, b)| {
+            let (a, b) = (channels(a), channels(b));
+            (0..2).flat_map(move |i| (0..3).map(move |c| a[i][c].abs_diff(b[i][c])))
+        })
+        .map(f32::from)
+        .sum::<f32>()
+        / (a.len() * 6) as f32
+}
+
+#[test]
+fn test_zoomed_out_views_render_from_a_smaller_level() {
+    let image = photo();
+    let view = View::fit(image.width, image.height, 40, 20);
+    let renderer = HalfBlock::default();
+
+    let from_pyramid = renderer.render_pyramid(&image, &view).unwrap();
+    let direct = renderer.render_cells(&image.data, &view).unwrap();
+
+    assert!(!image.pyramid.cached_levels().is_empty());
+    assert_eq!(from_pyramid.len(), direct.len());
+    // Level pixels only approximate the sample edges, so hard edges may shift slightly
+    let difference = mean_difference(&from_pyramid, &direct);
+    assert!(difference < 6.0, "mean difference {}", difference);
+}
+
+#[test]
+fn test_denser_glyphs_use_finer_levels() {
+    let image = photo();
+    let view = View::fit(image.width, image.height, 40, 20);
+
+    HalfBlock::new(RenderOptions {
+        glyphs: Glyphs::Braille,
+        ..Default::default()
+    })
+    .render_pyramid(&image, &view)
+    .unwrap();
+    let braille_levels = image.pyramid.cached_levels();
+    Ascii::default().render_pyramid(&image, &view).unwrap();
+
+    // Ascii has one pixel per cell, braille eight, so ascii reads a coarser level
+    assert!(image.pyramid.cached_levels().iter().max() > braille_levels.iter().max());
+}
+
+#[test]
+fn test_small_views_read_the_original() {
+    let image = photo().with_data(DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+        10,
+        10,
+        Rgba([1, 2, 3, 255]),
+    )));
+    let view = View::fit(10, 10, 10, 5);
+
+    HalfBlock::default().render_pyramid(&image, &view).unwrap();
+
+    assert!(image.pyramid.cached_levels().is_empty());
+}
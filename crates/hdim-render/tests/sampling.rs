@@ -0,0 +1,281 @@
+use hdim_render::color::TerminalColor;
+use hdim_render::sample::sample_grid;
+use hdim_render::{Background, ColorSpace, Filter, RenderOptions, View, render_cells};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// A busy, deterministic test pattern with an odd size.
+fn pattern() -> DynamicImage {
+    let buffer = RgbaImage::from_fn(97, 61, |x, y| {
+        Rgba([
+            ((x * 37 + y * 11) % 256) as u8,
+            ((x * x + y * 3) % 256) as u8,
+            ((y * y * 7 + x) % 256) as u8,
+            255,
+        ])
+    });
+    DynamicImage::ImageRgba8(buffer)
+}
+
+fn view(source: (u32, u32, u32, u32), target: (u32, u32)) -> View {
+    View {
+        source_x: source.0,
+        source_y: source.1,
+        source_width: source.2,
+        source_height: source.3,
+        target_width: target.0,
+        target_height: target.1,
+    }
+}
+
+fn mean(colors: impl Iterator<Item = [u8; 3]>) -> [f64; 3] {
+    let mut total = [0.0; 3];
+    let mut count = 0.0;
+    for color in colors {
+        for c in 0..3 {
+            total[c] += color[c] as f64;
+        }
+        count += 1.0;
+    }
+    total.map(|t| t / count)
+}
+
+fn region_mean(image: &DynamicImage, view: &View) -> [f64; 3] {
+    let image = image.to_rgb8();
+    mean(
+        (view.source_y..view.source_y + view.source_height).flat_map(|y| {
+            let image = &image;
+            (view.source_x..view.source_x + view.source_width).map(move |x| image.get_pixel(x, y).0)
+        }),
+    )
+}
+
+fn assert_close(actual: [f64; 3], expected: [f64; 3], tolerance: f64, context: &str) {
+    for c in 0..3 {
+        assert!(
+            (actual[c] - expected[c]).abs() <= tolerance,
+            "{}: {:?} != {:?}",
+            context,
+            actual,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_mean_color_is_preserved_across_zoom_levels() {
+    let image = pattern();
+    let sizes = [(97, 61), (40, 25), (33, 17), (13, 7), (7, 3), (1, 1)];
+    let regions = [(0, 0, 97, 61), (5, 3, 71, 49), (10, 20, 29, 31)];
+    for region in regions {
+        for target in sizes {
+            let view = view(region, target);
+            let samples = sample_grid(
+                &image,
+                &view,
+                target.0,
+                target.1,
+                Filter::Box,
+                ColorSpace::Srgb,
+                Background::default(),
+            );
+            assert_close(
+                mean(samples.into_iter()),
+                region_mean(&image, &view),
+                0.6,
+                &format!("region {:?} at {:?}", region, target),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_mean_color_is_preserved_by_half_blocks() {
+    let image = pattern();
+    for zoom in [0.5, 1.0, 1.5, 2.3, 3.7] {
+        let columns = (97.0 / zoom) as u32;
+        let rows = (61.0 / zoom / 2.0) as u32;
+        let view = view((0, 0, 97, 61), (columns, rows));
+        let options = RenderOptions {
+            averaging: ColorSpace::Srgb,
+            ..Default::default()
+        };
+        let cells = render_cells(&image, &view, &options).unwrap();
+        let colors = cells.iter().flat_map(|cell| {
+            [cell.bg, cell.fg].map(|color| match color {
+                Some(TerminalColor::Rgb(rgb)) => rgb,
+                other => panic!("expected truecolor, got {:?}", other),
+            })
+        });
+
+        assert_close(
+            mean(colors),
+            region_mean(&image, &view),
+            0.6,
+            &format!("zoom {}", zoom),
+        );
+    }
+}
+
+#[test]
+fn test_thin_lines_are_not_skipped() {
+    // A single white column at a position that used to fall between blocks
+    let buffer = RgbaImage::from_fn(30, 2, |x, _| {
+        if x == 10 {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([0, 0, 0, 255])
+        }
+    });
+    let image = DynamicImage::ImageRgba8(buffer);
+    let samples = sample_grid(
+        &image,
+        &view((0, 0, 30, 2), (7, 1)),
+        7,
+        1,
+        Filter::Box,
+        ColorSpace::Srgb,
+        Background::default(),
+    );
+
+    // The line covers 1/30 of the image, so 7 samples share 7/30 of its brightness
+    let total: u32 = samples.iter().map(|s| s[0] as u32).sum();
+    assert!((58..=62).contains(&total), "total brightness {}", total);
+}
+
+#[test]
+fn test_flat_image_has_no_seams() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(50, 50, Rgba([90, 140, 200, 255])));
+    for space in [ColorSpace::Srgb, ColorSpace::Linear, ColorSpace::Oklab] {
+        for filter in [Filter::Box, Filter::Lanczos3, Filter::Mitchell] {
+            for target in [(17, 9), (50, 50), (130, 77)] {
+                let samples = sample_grid(
+                    &image,
+                    &view((3, 7, 41, 37), target),
+                    target.0,
+                    target.1,
+                    filter,
+                    space,
+                    Background::default(),
+                );
+                assert!(
+                    samples.iter().all(|&s| s == [90, 140, 200]),
+                    "{:?} {:?} at {:?}",
+                    space,
+                    filter,
+                    target
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_upscaling_filters_preserve_mean() {
+    let image = pattern();
+    let view = view((20, 10, 16, 12), (64, 48));
+    for filter in [Filter::Lanczos3, Filter::Mitchell] {
+        let samples = sample_grid(
+            &image,
+            &view,
+            64,
+            48,
+            filter,
+            ColorSpace::Srgb,
+            Background::default(),
+        );
+        assert_close(
+            mean(samples.into_iter()),
+            region_mean(&image, &view),
+            6.0,
+            &format!("{:?}", filter),
+        );
+    }
+}
+
+#[test]
+fn test_upscaling_filters_differ_from_box() {
+    let image = pattern();
+    let view = view((20, 10, 8, 8), (32, 32));
+    let box_samples = sample_grid(
+        &image,
+        &view,
+        32,
+        32,
+        Filter::Box,
+        ColorSpace::Srgb,
+        Background::default(),
+    );
+
+    assert_ne!(
+        box_samples,
+        sample_grid(
+            &image,
+            &view,
+            32,
+            32,
+            Filter::Mitchell,
+            ColorSpace::Srgb,
+            Background::default()
+        )
+    );
+    assert_ne!(
+        box_samples,
+        sample_grid(
+            &image,
+            &view,
+            32,
+            32,
+            Filter::Lanczos3,
+            ColorSpace::Srgb,
+            Background::default()
+        )
+    );
+}
+
+#[test]
+fn test_pixel_formats_sample_alike() {
+    let rgba = pattern();
+    let view = view((5, 3, 80, 50), (23, 11));
+    let expected = sample_grid(
+        &rgba,
+        &view,
+        23,
+        22,
+        Filter::Box,
+        ColorSpace::Linear,
+        Background::default(),
+    );
+
+    for image in [
+        DynamicImage::ImageRgb8(rgba.to_rgb8()),
+        DynamicImage::ImageRgba16(rgba.to_rgba16()),
+        DynamicImage::ImageRgb32F(rgba.to_rgb32f()),
+    ] {
+        let samples = sample_grid(
+            &image,
+            &view,
+            23,
+            22,
+            Filter::Box,
+            ColorSpace::Linear,
+            Background::default(),
+        );
+        assert_eq!(samples, expected, "{:?}", image.color());
+    }
+}
+
+#[test]
+fn test_empty_grid() {
+    let image = pattern();
+    let samples = sample_grid(
+        &image,
+        &view((0, 0, 97, 61), (0, 0)),
+        0,
+        0,
+        Filter::Box,
+        ColorSpace::Linear,
+        Background::default(),
+    );
+
+    assert!(samples.is_empty());
+}
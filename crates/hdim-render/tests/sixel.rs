@@ -0,0 +1,105 @@
+use hdim_render::{Backend, Sixel, View};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// Counts `#<index>;2;<r>;<g>;<b>` color definitions.
+fn count_color_registers(output: &str) -> usize {
+    output
+        .split('#')
+        .filter(|part| {
+            let fields: Vec<&str> = part.split(';').collect();
+            fields.len() == 5 && fields[1] == "2"
+        })
+        .count()
+}
+
+#[test]
+fn test_sixel_two_color_image() {
+    // Left half red, right half blue, one 4x6 cell
+    let mut buffer = RgbaImage::new(4, 6);
+    for (x, _, pixel) in buffer.enumerate_pixels_mut() {
+        *pixel = if x < 2 {
+            Rgba([255, 0, 0, 255])
+        } else {
+            Rgba([0, 0, 255, 255])
+        };
+    }
+    let image = DynamicImage::ImageRgba8(buffer);
+    let sixel = Sixel {
+        colors: 2,
+        cell_width: 4,
+        cell_height: 6,
+    };
+
+    let output = sixel.render(&image, &View::full(&image, 1, 1)).unwrap();
+
+    // Each color fills all six rows ('~') of its two columns
+    assert_eq!(
+        output,
+        "\x1bP0;1;0q\"1;1;4;6#0;2;100;0;0#1;2;0;0;100#0~~$#1??~~-\x1b\\"
+    );
+}
+
+#[test]
+fn test_sixel_respects_color_count() {
+    let mut buffer = RgbaImage::new(64, 64);
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        *pixel = Rgba([(x * 4) as u8, (y * 4) as u8, 128, 255]);
+    }
+    let image = DynamicImage::ImageRgba8(buffer);
+    let view = View::full(&image, 8, 4);
+
+    for colors in [2, 16, 256] {
+        let sixel = Sixel {
+            colors,
+            cell_width: 8,
+            cell_height: 16,
+        };
+        let output = sixel.render(&image, &view).unwrap();
+
+        assert!(count_color_registers(&output) <= colors as usize);
+        assert!(output.starts_with("\x1bP0;1;0q\"1;1;64;64"));
+        assert!(output.ends_with("\x1b\\"));
+    }
+}
+
+#[test]
+fn test_sixel_run_length_encoding() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(20, 6, Rgba([0, 255, 0, 255])));
+    let sixel = Sixel {
+        colors: 2,
+        cell_width: 20,
+        cell_height: 6,
+    };
+
+    let output = sixel.render(&image, &View::full(&image, 1, 1)).unwrap();
+
+    assert!(output.ends_with("#0;2;0;100;0#0!20~-\x1b\\"));
+}
+
+#[test]
+fn test_sixel_leaves_transparent_pixels_out() {
+    let mut buffer = RgbaImage::from_pixel(2, 6, Rgba([0, 0, 0, 0]));
+    buffer.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    let image = DynamicImage::ImageRgba8(buffer);
+    let sixel = Sixel {
+        colors: 4,
+        cell_width: 2,
+        cell_height: 6,
+    };
+
+    let output = sixel.render(&image, &View::full(&image, 1, 1)).unwrap();
+
+    // Only the top pixel of the first column is drawn
+    assert!(output.ends_with("#0;2;100;100;100#0@-\x1b\\"));
+}
+
+#[test]
+fn test_sixel_rejects_invalid_color_count() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::new(2, 2));
+    let sixel = Sixel {
+        colors: 1,
+        ..Sixel::default()
+    };
+
+    assert!(sixel.render(&image, &View::full(&image, 1, 1)).is_err());
+}
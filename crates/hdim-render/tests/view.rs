@@ -0,0 +1,60 @@
+use hdim_render::View;
+use image::DynamicImage;
+
+#[test]
+fn test_full_covers_the_whole_image() {
+    let view = View::full(&DynamicImage::new_rgb8(30, 20), 6, 5);
+
+    assert_eq!((view.source_x, view.source_y), (0, 0));
+    assert_eq!((view.source_width, view.source_height), (30, 20));
+    assert_eq!((view.target_width, view.target_height), (6, 5));
+}
+
+#[test]
+fn test_fit_limited_by_width() {
+    let view = View::fit(200, 100, 50, 100);
+
+    assert_eq!((view.target_width, view.target_height), (50, 13));
+    assert_eq!((view.source_width, view.source_height), (200, 100));
+}
+
+#[test]
+fn test_fit_limited_by_height() {
+    // 100 rows cover 200 pixels, so a 100x400 image is halved
+    let view = View::fit(100, 400, 200, 100);
+
+    assert_eq!((view.target_width, view.target_height), (50, 100));
+}
+
+#[test]
+fn test_fit_upscales_small_images() {
+    let view = View::fit(2, 2, 10, 10);
+
+    assert_eq!((view.target_width, view.target_height), (10, 5));
+}
+
+#[test]
+fn test_fit_never_returns_empty_view() {
+    let view = View::fit(1000, 1, 10, 10);
+
+    assert_eq!((view.target_width, view.target_height), (10, 1));
+}
+
+#[test]
+fn test_scaled_down_rounds_to_whole_pixels() {
+    let view = View {
+        source_x: 10,
+        source_y: 3,
+        source_width: 101,
+        source_height: 40,
+        target_width: 12,
+        target_height: 5,
+    };
+
+    let scaled = view.scaled_down(4);
+
+    // 10..111 becomes 2.5..27.75, 3..43 becomes 0.75..10.75
+    assert_eq!((scaled.source_x, scaled.source_width), (3, 25));
+    assert_eq!((scaled.source_y, scaled.source_height), (1, 10));
+    assert_eq!((scaled.target_width, scaled.target_height), (12, 5));
+}
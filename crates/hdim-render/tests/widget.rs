@@ -0,0 +1,103 @@
+#![cfg(feature = "ratatui")]
+
+use hdim_render::{
+    Ascii, Cell, CellRenderer, ColorDepth, HalfBlock, ImageWidget, RenderOptions, View,
+};
+use image::{DynamicImage, Rgba, RgbaImage};
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+fn test_image() -> DynamicImage {
+    let mut buffer = RgbaImage::new(2, 2);
+    buffer.put_pixel(0, 0, Rgba([255, 0, 0, 255])); // Red
+    buffer.put_pixel(1, 0, Rgba([0, 0, 255, 255])); // Blue
+    buffer.put_pixel(0, 1, Rgba([0, 255, 0, 255])); // Green
+    buffer.put_pixel(1, 1, Rgba([255, 255, 255, 255])); // White
+    DynamicImage::ImageRgba8(buffer)
+}
+
+fn draw(widget: &ImageWidget, area: Rect) -> Buffer {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 3));
+    widget.render(area, &mut buffer);
+    buffer
+}
+
+#[test]
+fn test_widget_writes_cells_into_buffer() {
+    let widget = ImageWidget::new(
+        &HalfBlock::default(),
+        &test_image(),
+        &View::full(&test_image(), 2, 1),
+    )
+    .unwrap();
+    let buffer = draw(&widget, Rect::new(1, 1, 3, 2));
+
+    let first = &buffer[(1, 1)];
+    assert_eq!(first.symbol(), "▄");
+    assert_eq!(first.bg, Color::Rgb(255, 0, 0));
+    assert_eq!(first.fg, Color::Rgb(0, 255, 0));
+    let second = &buffer[(2, 1)];
+    assert_eq!(second.bg, Color::Rgb(0, 0, 255));
+    assert_eq!(second.fg, Color::Rgb(255, 255, 255));
+    // Cells outside the view are left alone
+    assert_eq!(buffer[(3, 1)].symbol(), " ");
+    assert_eq!(buffer[(1, 2)].symbol(), " ");
+}
+
+#[test]
+fn test_widget_matches_ansi_renderer() {
+    let renderer = HalfBlock::default();
+    let cells = renderer
+        .render_cells(&test_image(), &View::full(&test_image(), 2, 1))
+        .unwrap();
+
+    assert_eq!(
+        hdim_render::write_ansi(&cells, 2).unwrap(),
+        hdim_render::render(&test_image(), &View::full(&test_image(), 2, 1)).unwrap()
+    );
+}
+
+#[test]
+fn test_widget_clips_to_area() {
+    let widget = ImageWidget::new(
+        &HalfBlock::default(),
+        &test_image(),
+        &View::full(&test_image(), 2, 1),
+    )
+    .unwrap();
+    let buffer = draw(&widget, Rect::new(0, 0, 1, 1));
+
+    assert_eq!(buffer[(0, 0)].symbol(), "▄");
+    assert_eq!(buffer[(1, 0)].symbol(), " ");
+    assert_eq!(buffer[(1, 0)].bg, Color::Reset);
+}
+
+#[test]
+fn test_widget_uses_named_ansi_colors() {
+    let renderer = HalfBlock::new(RenderOptions {
+        color_depth: ColorDepth::Ansi16,
+        ..Default::default()
+    });
+    let widget =
+        ImageWidget::new(&renderer, &test_image(), &View::full(&test_image(), 2, 1)).unwrap();
+    let buffer = draw(&widget, Rect::new(0, 0, 2, 1));
+
+    assert_eq!(buffer[(0, 0)].bg, Color::LightRed);
+    assert_eq!(buffer[(0, 0)].fg, Color::LightGreen);
+}
+
+#[test]
+fn test_widget_with_plain_cells() {
+    let ascii = Ascii::default()
+        .render_cells(&test_image(), &View::full(&test_image(), 2, 1))
+        .unwrap();
+    assert!(
+        ascii
+            .iter()
+            .all(|cell| cell.fg.is_none() && cell.bg.is_none())
+    );
+
+    let widget = ImageWidget::from_cells(vec![Cell::new('x', None, None); 2], 1);
+    let buffer = draw(&widget, Rect::new(0, 0, 4, 3));
+    assert_eq!(buffer[(0, 0)].symbol(), "x");
+    assert_eq!(buffer[(0, 1)].symbol(), "x");
+}
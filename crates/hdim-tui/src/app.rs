@@ -1,19 +1,29 @@
+use crate::cli::RenderArgs;
 use crate::components::exif_view::ExifView;
+use crate::components::resize::resize_limit_message;
+use crate::graphics::{self, GraphicsViewport};
 use color_eyre::eyre::{Ok, Result};
 use hdim_core::{
-    HdimImage,
+    HdimImage, Histogram, Operation, Pipeline, Size,
+    adjust::adjust,
+    crop,
     exif::ExifData,
-    state::{CropState, Tool},
+    orientation,
+    state::{AdjustState, CropState, ResizeState, Tool, TransformState},
+    transform::check_rotated_size,
+    viewport_source_size,
 };
+use hdim_render::{BackendKind, Background, CellRenderer, RenderCache, view::View};
+use image::metadata::Orientation;
 use std::{
     fs::File,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ActiveWidget {
     Main,
-    Tools,
     RightToolbar,
 }
 
@@ -21,18 +31,63 @@ pub enum ActiveWidget {
 pub enum AppMode {
     Normal,
     EditingCropValue,
+    EditingTransformValue,
+    EditingResizeValue,
     ExifView,
+    SavingAs,
+}
+
+/// Which pixels the histogram panel counts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HistogramSource {
+    /// The displayed image, i.e. with the pending crop
+    Crop,
+    /// The edited image without the pending crop
+    Whole,
+}
+
+/// Pixels the histogram with pending adjustments is computed from at most, so that changing them stays responsive
+const ADJUSTED_HISTOGRAM_PIXELS: f32 = 1024.0 * 1024.0;
+
+/// The pending adjustments applied to the visible part of the displayed image only.
+pub struct AdjustPreview {
+    /// The view of the displayed image, its generation and the adjustments the preview shows
+    key: (View, u64, AdjustState),
+    pub image: HdimImage,
+    /// The same area as the view of the displayed image, relative to `image`
+    pub view: View,
 }
 
 /// Application state
 pub struct App {
-    /// We store the wrapper HdimImage so we can re-render it and access metadata
-    pub hdim_image: HdimImage,
+    /// The source image and the history of edits applied to it
+    pub pipeline: Pipeline,
+    /// The image shown in the main viewport, i.e. the edited image with the pending crop applied
+    pub display_image: HdimImage,
+    /// Incremented whenever `display_image` changes
+    pub display_generation: u64,
+    /// Draws the viewport with a pixel graphics backend instead of half-block characters
+    pub graphics: Option<GraphicsViewport>,
+    /// Draws the viewport with characters when `graphics` is `None`
+    pub renderer: Box<dyn CellRenderer>,
+    /// The cells `renderer` drew last, cleared when `renderer` is replaced
+    pub render_cache: RenderCache,
+    /// Set when the screen is out of date, the event loop only draws then
+    pub redraw: bool,
+    /// The backend and options `renderer` was created from
+    pub backend_kind: BackendKind,
+    pub render_args: RenderArgs,
+    /// What shows through transparent pixels, cycled with 'b'
+    pub backgrounds: Vec<Background>,
+    /// Index of the current background in `backgrounds`
+    pub background: usize,
     /// The top-left corner of the viewport on the source image (x, y) in pixels.
     pub source_pos: (u32, u32),
     /// Zoom level. Represents `source_pixels / terminal_characters`.
     /// A smaller value is more zoomed in.
     pub zoom: f32,
+    /// Size of the main viewport in terminal cells, as laid out by the last `ui::render`
+    pub viewport: Size,
     /// Track the last time an input was processed to prevent double-triggering
     pub last_input_time: Instant,
     /// Minimum time between processing consecutive inputs
@@ -43,44 +98,478 @@ pub struct App {
     pub active_widget: ActiveWidget,
     // The state of the crop tool
     pub crop_state: CropState,
+    // Whether `crop_state` does not fit the image, so that `display_image` is uncropped
+    crop_ignored: bool,
     // The current application mode
     pub mode: AppMode,
     // The index of the selected crop option
     pub selected_crop_option_index: usize,
     // The input string for crop values
     pub crop_input: String,
-    // The EXIF data of the image
-    pub exif_data: Option<ExifData>,
+    // The pending free rotation of the transform tool
+    pub transform_state: TransformState,
+    // The edited image with the pending rotation, and the generation and rotation it was made from
+    rotation_cache: Option<((u64, TransformState), HdimImage)>,
+    // The index of the selected transform option
+    pub selected_transform_option_index: usize,
+    // The input string for the rotation angle
+    pub transform_input: String,
+    // The target size and settings of the resize tool
+    pub resize_state: ResizeState,
+    // The index of the selected resize option
+    pub selected_resize_option_index: usize,
+    // The input string for resize values
+    pub resize_input: String,
+    // The pending adjustments of the adjust tool
+    pub adjust_state: AdjustState,
+    // The index of the selected adjust option
+    pub selected_adjust_option_index: usize,
+    // What the main viewport shows instead of the displayed image while adjustments are pending
+    pub adjust_preview: Option<AdjustPreview>,
+    // What the histogram panel counts, `None` while it is hidden
+    pub histogram_source: Option<HistogramSource>,
+    // The histogram of the panel and what it was computed from
+    histogram_cache: Option<((HistogramSource, AdjustState), Histogram)>,
     // The state of the EXIF view
     pub exif_view: Option<ExifView>,
     // Whether to show the right toolbar
     pub show_right_toolbar: bool,
+    // The input string for the "Save as" path
+    pub save_input: String,
+    // A message shown in the bottom bar, e.g. the result of the last save
+    pub status: Option<String>,
 }
 
 impl App {
-    pub fn new(hdim_image: HdimImage, initial_zoom: f32) -> Result<Self> {
+    pub fn new(
+        hdim_image: HdimImage,
+        initial_zoom: f32,
+        graphics: Option<GraphicsViewport>,
+        backend_kind: BackendKind,
+        render_args: RenderArgs,
+        backgrounds: Vec<Background>,
+    ) -> Result<Self> {
         let mut file = File::open(hdim_image.path.clone())?;
-        let exif_data = ExifData::get_exif_data(&mut file).ok();
-        let exif_view = exif_data.as_ref().map(ExifView::new);
+        let exif_view = ExifData::get_exif_data(&mut file)
+            .ok()
+            .map(|exif_data| ExifView::new(&exif_data));
+        let size = Size {
+            width: hdim_image.width,
+            height: hdim_image.height,
+        };
 
         Ok(Self {
-            hdim_image,
+            display_image: hdim_image.clone(),
+            display_generation: 0,
+            graphics,
+            renderer: graphics::text_renderer(backend_kind, &render_args, backgrounds[0]),
+            render_cache: RenderCache::new(),
+            redraw: true,
+            backend_kind,
+            render_args,
+            backgrounds,
+            background: 0,
+            pipeline: Pipeline::new(hdim_image),
             source_pos: (0, 0),
             zoom: initial_zoom,
+            viewport: Size {
+                width: 0,
+                height: 0,
+            },
             last_input_time: Instant::now(),
             input_delay: Duration::from_millis(50), // Reduced for snappier input
             selected_tool: None,
             active_widget: ActiveWidget::Main,
             crop_state: CropState::default(),
+            crop_ignored: false,
             mode: AppMode::Normal,
             selected_crop_option_index: 0,
             crop_input: String::new(),
-            exif_data,
+            transform_state: TransformState::default(),
+            rotation_cache: None,
+            selected_transform_option_index: 0,
+            transform_input: String::new(),
+            resize_state: ResizeState::new(size),
+            selected_resize_option_index: 0,
+            resize_input: String::new(),
+            adjust_state: AdjustState::default(),
+            selected_adjust_option_index: 0,
+            adjust_preview: None,
+            histogram_source: None,
+            histogram_cache: None,
             exif_view,
             show_right_toolbar: true,
+            save_input: String::new(),
+            status: None,
+        })
+    }
+
+    /// Re-applies the edit history, the pending rotation and the pending crop to refresh the viewport.
+    ///
+    /// If the margins would leave an empty image the uncropped image is shown instead.
+    pub fn update_display_image(&mut self) {
+        self.display_generation += 1;
+        self.histogram_cache = None;
+        let edited = match self.pipeline.output() {
+            Result::Ok(edited) => edited.clone(),
+            Err(e) => {
+                self.status = Some(format!("Edit failed: {}", e));
+                self.display_image = self.pipeline.original().clone();
+                self.crop_ignored = true;
+                self.clamp_source_pos();
+                return;
+            }
+        };
+        // Rotated before the crop, like it will be once applied
+        let edited = match self.pending_rotation() {
+            Some(rotation) => self.rotated(edited, rotation),
+            None => {
+                self.rotation_cache = None;
+                edited
+            }
+        };
+        match crop(&edited, &self.crop_state) {
+            Result::Ok(cropped) => {
+                self.display_image = cropped;
+                self.crop_ignored = false;
+                self.status = None;
+            }
+            Err(e) => {
+                self.display_image = edited;
+                self.crop_ignored = true;
+                self.status = Some(format!("Crop ignored: {}", e));
+            }
+        }
+        self.clamp_source_pos();
+    }
+
+    /// Adds an operation to the edit history and shows the result.
+    pub fn push_operation(&mut self, operation: Operation) {
+        self.pipeline.push(operation);
+        self.update_display_image();
+    }
+
+    /// Commits the pending rotation, then the pending crop margins to the edit history.
+    pub fn apply_crop(&mut self) {
+        // The margins were chosen on the rotated preview
+        self.apply_rotation();
+        if self.crop_state == CropState::default() {
+            return;
+        }
+        let operation = Operation::Crop(self.crop_state);
+        self.crop_state = CropState::default();
+        self.source_pos = (0, 0);
+        self.push_operation(operation);
+    }
+
+    /// The free rotation of the transform tool as an operation, if its angle is not zero.
+    fn pending_rotation(&self) -> Option<Operation> {
+        let TransformState {
+            degrees,
+            interpolation,
+            fill,
+        } = self.transform_state;
+        (degrees != 0.0).then_some(Operation::RotateFree {
+            degrees,
+            interpolation,
+            fill,
         })
     }
 
+    /// `edited` with the pending `rotation`, reused while neither of them changes.
+    ///
+    /// Typing crop margins refreshes the display on every key, rotating a large image each time would lag.
+    fn rotated(&mut self, edited: HdimImage, rotation: Operation) -> HdimImage {
+        let key = (edited.generation, self.transform_state);
+        if let Some((cached, rotated)) = &self.rotation_cache
+            && *cached == key
+        {
+            return rotated.clone();
+        }
+        match rotation.apply(&edited.data) {
+            Result::Ok(rotated) => {
+                let rotated = edited.with_data(rotated);
+                self.rotation_cache = Some((key, rotated.clone()));
+                rotated
+            }
+            Err(e) => {
+                self.rotation_cache = None;
+                self.status = Some(format!("Rotation ignored: {}", e));
+                edited
+            }
+        }
+    }
+
+    /// Commits the pending free rotation to the edit history.
+    pub fn apply_rotation(&mut self) {
+        let Some(operation) = self.pending_rotation() else {
+            return;
+        };
+        if let Result::Ok(edited) = self.pipeline.output()
+            && let Err(e) =
+                check_rotated_size(edited.width, edited.height, self.transform_state.degrees)
+        {
+            self.status = Some(format!("Rotation not applied: {}", e));
+            return;
+        }
+        self.transform_state.degrees = 0.0;
+        self.source_pos = (0, 0);
+        self.push_operation(operation);
+    }
+
+    /// The size of the image the resize tool resizes, i.e. the displayed image.
+    pub fn resize_source(&self) -> Size {
+        Size {
+            width: self.display_image.width,
+            height: self.display_image.height,
+        }
+    }
+
+    /// Opens the resize tool with the current image size as the target.
+    pub fn begin_resize(&mut self) {
+        let source = self.resize_source();
+        self.resize_state.width = source.width;
+        self.resize_state.height = source.height;
+        self.selected_tool = Some(Tool::Resize);
+    }
+
+    /// Commits the pending rotation and crop, then resizes the result.
+    pub fn apply_resize(&mut self) {
+        // The target was chosen for the displayed image, so it has to be what gets resized
+        self.apply_crop();
+        if self.resize_state.output_size(self.resize_source()) == self.resize_source() {
+            return;
+        }
+        // Switching to fill can make a target too large after it was entered
+        if !self.resize_state.fits_limit(self.resize_source()) {
+            self.status = Some(resize_limit_message(&self.resize_state));
+            return;
+        }
+        self.source_pos = (0, 0);
+        self.push_operation(self.resize_state.operation());
+        let source = self.resize_source();
+        self.resize_state.width = source.width;
+        self.resize_state.height = source.height;
+    }
+
+    /// Commits the pending adjustments to the edit history.
+    pub fn apply_adjustments(&mut self) {
+        if self.adjust_state.is_neutral() {
+            return;
+        }
+        let operation = Operation::Adjust(self.adjust_state);
+        self.adjust_state = AdjustState::default();
+        self.push_operation(operation);
+    }
+
+    /// Adjusts the part of the displayed image that `view` shows, unless the preview is current.
+    ///
+    /// Only the pyramid level the renderer would read is adjusted, given that a
+    /// cell shows `cell_pixels` pixels, so the cost follows the terminal size
+    /// rather than the image size.
+    pub fn update_adjust_preview(&mut self, view: View, cell_pixels: (u32, u32)) {
+        if self.adjust_state.is_neutral() {
+            if self.adjust_preview.take().is_some() {
+                // The render cache must not mistake the displayed image for the preview
+                self.display_generation += 1;
+            }
+            return;
+        }
+        let key = (view, self.display_generation, self.adjust_state);
+        if self
+            .adjust_preview
+            .as_ref()
+            .is_some_and(|preview| preview.key == key)
+        {
+            return;
+        }
+
+        let (cell_columns, cell_rows) = cell_pixels;
+        let scale = (view.source_width as f32 / (view.target_width * cell_columns).max(1) as f32)
+            .min(view.source_height as f32 / (view.target_height * cell_rows).max(1) as f32);
+        let level = self.display_image.level_for(scale);
+        let (source, scaled) = match &level {
+            Some(level) => (level.image.as_ref(), view.scaled_down(level.factor)),
+            None => (&self.display_image.data, view),
+        };
+        let visible = source.crop_imm(
+            scaled.source_x,
+            scaled.source_y,
+            scaled.source_width,
+            scaled.source_height,
+        );
+        let image = self
+            .display_image
+            .with_data(adjust(&visible, &self.adjust_state));
+
+        self.display_generation += 1;
+        self.adjust_preview = Some(AdjustPreview {
+            key: (view, self.display_generation, self.adjust_state),
+            image,
+            view: View {
+                source_x: 0,
+                source_y: 0,
+                ..scaled
+            },
+        });
+    }
+
+    /// Cycles the histogram panel through the crop, the whole image and hidden.
+    pub fn cycle_histogram(&mut self) {
+        self.histogram_source = match self.histogram_source {
+            None => Some(HistogramSource::Crop),
+            Some(HistogramSource::Crop) => Some(HistogramSource::Whole),
+            Some(HistogramSource::Whole) => None,
+        };
+    }
+
+    /// The histogram the panel shows, computed again when the image or the pending adjustments changed.
+    ///
+    /// Pending adjustments are applied to a pyramid level of at most about a
+    /// megapixel, so their histogram is close but not exact.
+    pub fn histogram(&mut self) -> Option<&Histogram> {
+        let key = (self.histogram_source?, self.adjust_state);
+        if self
+            .histogram_cache
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != key)
+        {
+            let image = match key.0 {
+                HistogramSource::Crop => &self.display_image,
+                HistogramSource::Whole => self.pipeline.output().unwrap_or(&self.display_image),
+            };
+            let histogram = if self.adjust_state.is_neutral() {
+                Histogram::new(&image.data)
+            } else {
+                // Levels are at least as fine as asked for, twice the scale keeps them below the limit
+                let pixels = image.width as f32 * image.height as f32;
+                let scale = 2.0 * (pixels / ADJUSTED_HISTOGRAM_PIXELS).sqrt();
+                let level = image.level_for(scale);
+                let data = level
+                    .as_ref()
+                    .map_or(&image.data, |level| level.image.as_ref());
+                Histogram::new(&adjust(data, &self.adjust_state))
+            };
+            self.histogram_cache = Some((key, histogram));
+        }
+        self.histogram_cache
+            .as_ref()
+            .map(|(_, histogram)| histogram)
+    }
+
+    /// Reverts the most recent edit.
+    pub fn undo(&mut self) {
+        let message = match self.pipeline.undo() {
+            Some(operation) => format!("Undid {}", operation),
+            None => "Nothing to undo".to_string(),
+        };
+        self.update_display_image();
+        self.status = Some(message);
+    }
+
+    /// Re-applies the most recently undone edit.
+    pub fn redo(&mut self) {
+        let message = match self.pipeline.redo() {
+            Some(operation) => format!("Redid {}", operation),
+            None => "Nothing to redo".to_string(),
+        };
+        self.update_display_image();
+        self.status = Some(message);
+    }
+
+    /// Sets the crop margins so that only the area visible in the main viewport remains.
+    pub fn crop_to_viewport(&mut self) {
+        let displayed = Size {
+            width: self.display_image.width,
+            height: self.display_image.height,
+        };
+        // The viewport is relative to the displayed image, which is only cropped if the margins fit
+        let current = if self.crop_ignored {
+            CropState::default()
+        } else {
+            self.crop_state
+        };
+        let visible =
+            CropState::from_viewport(displayed, self.source_pos, self.zoom, self.viewport);
+        self.crop_state = CropState {
+            left: current.left + visible.left,
+            right: current.right + visible.right,
+            top: current.top + visible.top,
+            bottom: current.bottom + visible.bottom,
+        };
+        self.source_pos = (0, 0);
+        self.update_display_image();
+    }
+
+    /// Starts the "Save as" prompt with a path next to the source image.
+    pub fn begin_save_as(&mut self) {
+        self.save_input = suggested_save_path(&self.pipeline.original().path)
+            .to_string_lossy()
+            .into_owned();
+        self.mode = AppMode::SavingAs;
+    }
+
+    /// Writes the displayed image to the path entered in the "Save as" prompt.
+    pub fn save_as(&mut self) {
+        let path = PathBuf::from(self.save_input.trim());
+        // What is on screen gets saved, including adjustments that are only previewed
+        let saved = if self.adjust_state.is_neutral() {
+            self.display_image.save_as(&path)
+        } else {
+            let adjusted = adjust(&self.display_image.data, &self.adjust_state);
+            self.display_image.with_data(adjusted).save_as(&path)
+        };
+        self.status = Some(match saved {
+            Result::Ok(()) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Save failed: {}", e),
+        });
+        self.save_input.clear();
+        self.mode = AppMode::Normal;
+    }
+
+    /// Switches to the next background for transparent pixels.
+    ///
+    /// Only character backends draw a background, graphics protocols leave transparency to the terminal.
+    pub fn cycle_background(&mut self) {
+        if self.graphics.is_some() {
+            self.status = Some(
+                "Graphics protocols show transparent pixels over the terminal background"
+                    .to_string(),
+            );
+            return;
+        }
+        self.background = (self.background + 1) % self.backgrounds.len();
+        let background = self.backgrounds[self.background];
+        self.renderer = graphics::text_renderer(self.backend_kind, &self.render_args, background);
+        self.render_cache.clear();
+        self.status = Some(match background {
+            Background::Color([r, g, b]) => format!("Background: #{:02x}{:02x}{:02x}", r, g, b),
+            Background::Checkerboard => "Background: checkerboard".to_string(),
+        });
+    }
+
+    /// Switches between the upright image and its pixels as stored, keeping the edits.
+    pub fn toggle_orientation(&mut self) {
+        let original = self.pipeline.original();
+        if original.orientation == Orientation::NoTransforms {
+            self.status = Some("The image has no EXIF orientation".to_string());
+            return;
+        }
+        let toggled = original.with_orientation(!original.oriented);
+        let message = if toggled.oriented {
+            format!(
+                "EXIF orientation applied: {}",
+                orientation::describe(toggled.orientation)
+            )
+        } else {
+            "Showing the pixels as stored".to_string()
+        };
+        self.pipeline.replace_original(toggled);
+        self.source_pos = (0, 0);
+        self.update_display_image();
+        self.status.get_or_insert(message);
+    }
+
     /// Adjusts the zoom level.
     pub fn zoom(&mut self, factor: f32) {
         self.zoom *= factor;
@@ -91,6 +580,27 @@ impl App {
         self.clamp_source_pos();
     }
 
+    /// Rounds a pan of `pixels` down to whole cells along each axis, as (x, y).
+    ///
+    /// The render cache can then reuse the cells that stay on screen. Where whole
+    /// cells would be more than `pixels`, the pan is left as it is.
+    pub fn pan_step(&self, pixels: u32) -> (u32, u32) {
+        let source = viewport_source_size(self.viewport, self.zoom);
+        let axis = |source: u32, target: u32| {
+            // Any multiple of `unit` pixels is a whole number of cells
+            let unit = source / gcd(source, target).max(1);
+            if unit > 0 && unit <= pixels {
+                pixels / unit * unit
+            } else {
+                pixels
+            }
+        };
+        (
+            axis(source.width, self.viewport.width),
+            axis(source.height, self.viewport.height),
+        )
+    }
+
     /// Moves the viewport on the source image.
     pub fn scroll(&mut self, dx: i32, dy: i32) {
         self.source_pos.0 = self.source_pos.0.saturating_add_signed(dx);
@@ -100,8 +610,8 @@ impl App {
 
     // Prevents the viewport from going out of bounds of the source image.
     pub fn clamp_source_pos(&mut self) {
-        let image_width = self.hdim_image.width;
-        let image_height = self.hdim_image.height;
+        let image_width = self.display_image.width;
+        let image_height = self.display_image.height;
         if self.source_pos.0 > image_width {
             self.source_pos.0 = image_width;
         }
@@ -110,3 +620,143 @@ impl App {
         }
     }
 }
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Builds `<stem>_edited.<ext>` in the directory of `path`.
+fn suggested_save_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "image".to_string());
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}_edited.{}", stem, ext.to_string_lossy()),
+        None => format!("{}_edited", stem),
+    };
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use image::{DynamicImage, RgbImage};
+
+    #[derive(Parser)]
+    struct Args {
+        #[command(flatten)]
+        render: RenderArgs,
+    }
+
+    fn make_app(name: &str, w: u32, h: u32) -> App {
+        let path =
+            std::env::temp_dir().join(format!("hdim-app-{}-{}.png", std::process::id(), name));
+        DynamicImage::ImageRgb8(RgbImage::new(w, h))
+            .save(&path)
+            .unwrap();
+        let image = HdimImage::from_path(&path).unwrap();
+        let app = App::new(
+            image,
+            1.0,
+            None,
+            BackendKind::TrueColor,
+            Args::parse_from(["hdim"]).render,
+            vec![Background::default()],
+        )
+        .unwrap();
+        std::fs::remove_file(path).unwrap();
+        app
+    }
+
+    #[test]
+    fn test_apply_crop_applies_the_pending_rotation_first() {
+        let mut app = make_app("crop", 40, 20);
+        app.transform_state.degrees = 90.0;
+        app.crop_state.bottom = 10;
+        app.update_display_image();
+        let preview = (app.display_image.width, app.display_image.height);
+
+        app.apply_crop();
+
+        assert_eq!(preview, (20, 30));
+        assert_eq!((app.display_image.width, app.display_image.height), preview);
+        assert!(matches!(
+            app.pipeline.operations(),
+            [Operation::RotateFree { .. }, Operation::Crop(_)]
+        ));
+        assert_eq!(app.transform_state.degrees, 0.0);
+    }
+
+    #[test]
+    fn test_crop_to_viewport_replaces_margins_that_do_not_fit() {
+        let mut app = make_app("viewport", 40, 20);
+        app.crop_state.left = 50;
+        app.update_display_image();
+        app.viewport = Size {
+            width: 10,
+            height: 5,
+        };
+
+        app.crop_to_viewport();
+
+        assert_eq!(
+            app.crop_state,
+            CropState {
+                left: 0,
+                right: 30,
+                top: 0,
+                bottom: 10,
+            }
+        );
+        assert_eq!(
+            (app.display_image.width, app.display_image.height),
+            (10, 10)
+        );
+    }
+
+    #[test]
+    fn test_apply_resize_refuses_targets_above_the_pixel_limit() {
+        let mut app = make_app("resize", 40, 20);
+        app.resize_state.width = 100_000;
+        app.resize_state.height = 100_000;
+
+        app.apply_resize();
+
+        assert!(app.pipeline.operations().is_empty());
+        assert!(app.status.as_deref().unwrap().contains("too large"));
+    }
+
+    #[test]
+    fn test_cycle_background_is_reported_with_graphics() {
+        let mut app = make_app("background", 4, 4);
+        app.graphics = Some(GraphicsViewport::new(Box::new(hdim_render::Iterm)));
+
+        app.cycle_background();
+
+        assert_eq!(app.background, 0);
+        assert!(
+            app.status
+                .as_deref()
+                .unwrap()
+                .contains("terminal background")
+        );
+    }
+
+    #[test]
+    fn test_pending_rotation_is_reused_while_cropping() {
+        let mut app = make_app("rotation", 40, 20);
+        app.transform_state.degrees = 30.0;
+        app.update_display_image();
+        let rotated = app.rotation_cache.as_ref().unwrap().1.generation;
+
+        app.crop_state.left = 5;
+        app.update_display_image();
+        assert_eq!(app.rotation_cache.as_ref().unwrap().1.generation, rotated);
+
+        app.transform_state.degrees = 45.0;
+        app.update_display_image();
+        assert_ne!(app.rotation_cache.as_ref().unwrap().1.generation, rotated);
+    }
+}
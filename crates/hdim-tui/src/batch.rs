@@ -0,0 +1,167 @@
+use crate::cli::EditArgs;
+use clap::ArgMatches;
+use color_eyre::eyre::{Result, bail, eyre};
+use hdim_core::{HdimImage, Operation, apply_all};
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+/// Every input was edited and written.
+pub const EXIT_OK: i32 = 0;
+/// At least one input could not be read, edited or written.
+pub const EXIT_FAILURE: i32 = 1;
+/// The arguments were invalid, e.g. `--output` with several inputs.
+pub const EXIT_USAGE: i32 = 2;
+
+/// Runs `hdim edit` and returns the process exit code.
+///
+/// Inputs are processed independently, so one broken file does not stop the rest.
+pub fn run(args: &EditArgs, matches: &ArgMatches) -> i32 {
+    let inputs = match expand_inputs(&args.inputs) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("hdim: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+    if inputs.is_empty() {
+        eprintln!("hdim: no input images");
+        return EXIT_USAGE;
+    }
+    if args.output.is_none() && args.out_dir.is_none() {
+        eprintln!("hdim: either --output or --out-dir is required");
+        return EXIT_USAGE;
+    }
+    if args.output.is_some() && inputs.len() > 1 {
+        eprintln!("hdim: --output needs exactly one input, use --out-dir for several");
+        return EXIT_USAGE;
+    }
+
+    // Nothing is written when two inputs would overwrite each other
+    let outputs: Vec<PathBuf> = inputs
+        .iter()
+        .map(|input| output_path(args, input))
+        .collect();
+    if let Some((first, second, output)) = find_collision(&inputs, &outputs) {
+        eprintln!(
+            "hdim: {} and {} would both be written to {}",
+            first.display(),
+            second.display(),
+            output.display()
+        );
+        return EXIT_USAGE;
+    }
+    if !args.overwrite
+        && let Some(input) = inputs
+            .iter()
+            .zip(&outputs)
+            .find_map(|(input, output)| is_same_file(input, output).then_some(input))
+    {
+        eprintln!(
+            "hdim: {} would be written over, use --overwrite to allow it",
+            input.display()
+        );
+        return EXIT_USAGE;
+    }
+
+    if let Some(out_dir) = &args.out_dir
+        && let Err(e) = std::fs::create_dir_all(out_dir)
+    {
+        eprintln!("hdim: {}: {}", out_dir.display(), e);
+        return EXIT_FAILURE;
+    }
+
+    let operations = args.operations(matches);
+    let mut exit_code = EXIT_OK;
+    for (input, output) in inputs.iter().zip(&outputs) {
+        match edit_file(input, output, &operations) {
+            Ok(()) => eprintln!("{} -> {}", input.display(), output.display()),
+            Err(e) => {
+                eprintln!("hdim: {}: {}", input.display(), e);
+                exit_code = EXIT_FAILURE;
+            }
+        }
+    }
+    exit_code
+}
+
+fn edit_file(input: &Path, output: &Path, operations: &[Operation]) -> Result<()> {
+    let image = HdimImage::from_path(input).map_err(|e| eyre!("{}", e))?;
+    let edited = image.with_data(apply_all(&image.data, operations)?);
+    edited.save_as(output)?;
+    Ok(())
+}
+
+/// Resolves `-` to paths read from stdin and expands glob patterns.
+///
+/// Arguments without glob characters are taken literally so missing files are reported later.
+fn expand_inputs(inputs: &[String]) -> Result<Vec<PathBuf>> {
+    if inputs.iter().filter(|input| *input == "-").count() > 1 {
+        bail!("`-` can only be given once, stdin is read only once");
+    }
+    let mut paths = Vec::new();
+    for input in inputs {
+        if input == "-" {
+            for line in io::stdin().lock().lines() {
+                let line = line?;
+                let line = line.trim();
+                if !line.is_empty() {
+                    paths.push(PathBuf::from(line));
+                }
+            }
+        } else if input.contains(['*', '?', '[']) {
+            let matches = glob::glob(input)?.collect::<Result<Vec<_>, _>>()?;
+            if matches.is_empty() {
+                bail!("no files match {}", input);
+            }
+            paths.extend(matches);
+        } else {
+            paths.push(PathBuf::from(input));
+        }
+    }
+    Ok(paths)
+}
+
+fn output_path(args: &EditArgs, input: &Path) -> PathBuf {
+    if let Some(output) = &args.output {
+        return output.clone();
+    }
+
+    let mut path = args.out_dir.clone().unwrap_or_default();
+    path.push(input.file_name().unwrap_or(input.as_os_str()));
+    if let Some(format) = &args.format {
+        path.set_extension(format);
+    }
+    path
+}
+
+/// The first two inputs that map to the same output path, and that path.
+fn find_collision<'a>(
+    inputs: &'a [PathBuf],
+    outputs: &'a [PathBuf],
+) -> Option<(&'a Path, &'a Path, &'a Path)> {
+    let mut seen: HashMap<&Path, &Path> = HashMap::new();
+    for (input, output) in inputs.iter().zip(outputs) {
+        if let Some(first) = seen.insert(output, input) {
+            return Some((first, input, output));
+        }
+    }
+    None
+}
+
+/// Whether `output` names the existing file `input`, however the two paths are spelled.
+///
+/// An output whose directory does not exist yet cannot be an input.
+fn is_same_file(input: &Path, output: &Path) -> bool {
+    let Ok(input) = input.canonicalize() else {
+        return false;
+    };
+    let directory = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    match (directory.canonicalize(), output.file_name()) {
+        (Ok(directory), Some(name)) => directory.join(name) == input,
+        _ => false,
+    }
+}
@@ -0,0 +1,430 @@
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use hdim_core::{Flip, Operation, ResizeMode, Rotation, state::CropState};
+use hdim_render::{DotColor, Threshold};
+use image::imageops::FilterType;
+use std::path::PathBuf;
+
+/// hdim - High Definition Image Manipulator
+#[derive(Debug, Parser)]
+#[command(name = "hdim", version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Image to open in the interactive viewer
+    pub path: Option<PathBuf>,
+    /// Print the image to stdout instead of opening the viewer, like `hdim show`
+    #[arg(long, requires = "path")]
+    pub print: bool,
+    /// Memory in MiB for the smaller copies of the image that speed up zooming out
+    #[arg(long, default_value_t = 256)]
+    pub pyramid_memory: usize,
+    /// Show the pixels as stored instead of turning them upright by their EXIF orientation
+    #[arg(long)]
+    pub raw_orientation: bool,
+    #[command(flatten)]
+    pub render: RenderArgs,
+}
+
+/// Options for drawing images, shared by the viewer and `hdim show`.
+#[derive(Clone, Debug, Args)]
+pub struct RenderArgs {
+    /// How images are drawn. `auto` asks the terminal what it supports
+    #[arg(long, value_enum, default_value_t = BackendArg::Auto)]
+    pub backend: BackendArg,
+    /// Number of palette colors used by the sixel backend
+    #[arg(long, default_value_t = 256, value_parser = clap::value_parser!(u16).range(2..=256))]
+    pub sixel_colors: u16,
+    /// Filter for character backends when zoomed in past the image resolution
+    #[arg(long, value_enum, default_value_t = ZoomFilterArg::Box)]
+    pub zoom_filter: ZoomFilterArg,
+    /// Color space pixels are averaged in when several share a cell
+    #[arg(long, value_enum, default_value_t = AveragingArg::Linear)]
+    pub averaging: AveragingArg,
+    /// Block characters used by the truecolor, ansi256 and ansi16 backends
+    #[arg(long, value_enum, default_value_t = GlyphsArg::HalfBlock)]
+    pub glyphs: GlyphsArg,
+    /// Which pixels get a dot with `--glyphs braille`: a brightness from 0 to 255, `otsu` or `dither`
+    #[arg(long, default_value = "otsu", value_parser = parse_threshold)]
+    pub threshold: Threshold,
+    /// Color of braille dots: `average` or a hex color like `#33ff66`
+    #[arg(long, default_value = "average", value_parser = parse_dot_color)]
+    pub dot_color: DotColor,
+    /// Put braille dots on dark pixels, for dark lines on a light background
+    #[arg(long)]
+    pub invert_dots: bool,
+    /// Characters from dark to bright for the ascii backend
+    #[arg(long, default_value = hdim_render::ascii::DEFAULT_RAMP)]
+    pub ramp: String,
+    /// Draw edges with line characters in the ascii backend
+    #[arg(long)]
+    pub edges: bool,
+    /// Dithering for the ansi256 and ansi16 backends
+    #[arg(long, value_enum, default_value_t = DitherArg::None)]
+    pub dither: DitherArg,
+    /// What shows through transparent pixels: `terminal`, `checkerboard` or a hex color like `#1e1e2e`
+    #[arg(long, default_value = "terminal", value_parser = parse_background)]
+    pub background: BackgroundArg,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackgroundArg {
+    /// The terminal's default background color, as reported by the terminal
+    Terminal,
+    Checkerboard,
+    Color([u8; 3]),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ZoomFilterArg {
+    /// Sharp pixel edges
+    Box,
+    /// Sharp, may ring at hard edges
+    Lanczos,
+    /// Smooth
+    Mitchell,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum AveragingArg {
+    /// Raw sRGB values, the classic look
+    Srgb,
+    /// Linear light, keeps fine bright detail bright
+    Linear,
+    /// Perceptual lightness and hue
+    Oklab,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum GlyphsArg {
+    /// Two pixels per cell, works with every font
+    HalfBlock,
+    /// Four pixels per cell
+    Quadrant,
+    /// Six pixels per cell, needs a font with Symbols for Legacy Computing
+    Sextant,
+    /// Eight pixels per cell, needs a font with the Unicode 16 octants
+    Octant,
+    /// Eight braille dots per cell in one color, for line art and screenshots
+    Braille,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum DitherArg {
+    /// Use the nearest palette color
+    None,
+    /// Floyd-Steinberg error diffusion
+    FloydSteinberg,
+    /// 4x4 Bayer matrix, does not shimmer while panning
+    Ordered,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum BackendArg {
+    /// Detect the best backend for the terminal
+    Auto,
+    /// kitty graphics protocol
+    Kitty,
+    /// DEC Sixel graphics
+    Sixel,
+    /// iTerm2 inline images (OSC 1337)
+    Iterm,
+    /// Truecolor half-block characters
+    #[value(alias = "half-block")]
+    Truecolor,
+    /// Half-block characters with the xterm 256 color palette
+    Ansi256,
+    /// Half-block characters with the 16 ANSI colors
+    Ansi16,
+    /// Plain text without escape codes, e.g. to paste into an issue
+    Ascii,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Apply edits to one or more images without starting the terminal UI
+    Edit(Box<EditArgs>),
+    /// Print an image to stdout and exit
+    Show(ShowArgs),
+}
+
+/// Arguments of `hdim show`.
+#[derive(Debug, Args)]
+pub struct ShowArgs {
+    /// Image to print
+    pub path: PathBuf,
+    /// Maximum width in terminal columns. Defaults to the terminal width
+    #[arg(long)]
+    pub width: Option<u32>,
+    /// Maximum height in terminal rows. Defaults to the terminal height
+    #[arg(long)]
+    pub height: Option<u32>,
+    /// Print the pixels as stored instead of turning them upright by their EXIF orientation
+    #[arg(long)]
+    pub raw_orientation: bool,
+    #[command(flatten)]
+    pub render: RenderArgs,
+}
+
+/// Arguments of `hdim edit`.
+///
+/// Edits are applied in the order they appear on the command line.
+#[derive(Debug, Args)]
+pub struct EditArgs {
+    /// Input images or glob patterns. Use `-` to read paths from stdin, one per line
+    #[arg(required = true)]
+    pub inputs: Vec<String>,
+    /// Output file; only valid with a single input
+    #[arg(short, long, conflicts_with = "out_dir")]
+    pub output: Option<PathBuf>,
+    /// Directory to write the edited images to, keeping their file names. Inputs with the same file name are rejected
+    #[arg(short = 'd', long)]
+    pub out_dir: Option<PathBuf>,
+    /// Extension of the files written to --out-dir, e.g. `png`. Defaults to the input's
+    #[arg(long, requires = "out_dir")]
+    pub format: Option<String>,
+    /// Allow writing an edited image over its input file
+    #[arg(long)]
+    pub overwrite: bool,
+    /// Crop margins in pixels
+    #[arg(long, value_name = "LEFT,RIGHT,TOP,BOTTOM", value_parser = parse_crop)]
+    pub crop: Vec<CropState>,
+    /// Rotate clockwise by 90, 180 or 270 degrees
+    #[arg(long, value_name = "DEGREES", value_parser = parse_rotation)]
+    pub rotate: Vec<Rotation>,
+    /// Mirror the image
+    #[arg(long, value_enum)]
+    pub flip: Vec<FlipArg>,
+    /// Resize to exactly the given size
+    #[arg(long, value_name = "WIDTHxHEIGHT", value_parser = parse_size)]
+    pub resize: Vec<(u32, u32)>,
+    /// How --resize treats a size with another aspect ratio than the image
+    #[arg(long, value_enum, default_value_t = ResizeModeArg::Exact)]
+    pub resize_mode: ResizeModeArg,
+    /// Resampling filter used by --resize
+    #[arg(long, value_enum, default_value_t = FilterArg::Lanczos3)]
+    pub filter: FilterArg,
+    /// Add a value to every color channel
+    #[arg(long, allow_negative_numbers = true)]
+    pub brighten: Vec<i32>,
+    /// Adjust the contrast; negative values decrease it
+    #[arg(long, allow_negative_numbers = true)]
+    pub contrast: Vec<f32>,
+    /// Rotate the hue by the given number of degrees
+    #[arg(long, allow_negative_numbers = true)]
+    pub hue: Vec<i32>,
+    /// Convert to grayscale
+    #[arg(long)]
+    pub grayscale: bool,
+    /// Invert all colors
+    #[arg(long)]
+    pub invert: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum FlipArg {
+    #[value(alias = "h")]
+    Horizontal,
+    #[value(alias = "v")]
+    Vertical,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum FilterArg {
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ResizeModeArg {
+    /// Stretch to exactly the given size
+    Exact,
+    /// Keep the aspect ratio and fit inside the given size
+    Fit,
+    /// Keep the aspect ratio, cover the given size and crop the rest
+    Fill,
+}
+
+impl From<ResizeModeArg> for ResizeMode {
+    fn from(mode: ResizeModeArg) -> Self {
+        match mode {
+            ResizeModeArg::Exact => ResizeMode::Exact,
+            ResizeModeArg::Fit => ResizeMode::Fit,
+            ResizeModeArg::Fill => ResizeMode::Fill,
+        }
+    }
+}
+
+impl From<FilterArg> for FilterType {
+    fn from(filter: FilterArg) -> Self {
+        match filter {
+            FilterArg::Nearest => FilterType::Nearest,
+            FilterArg::Triangle => FilterType::Triangle,
+            FilterArg::CatmullRom => FilterType::CatmullRom,
+            FilterArg::Gaussian => FilterType::Gaussian,
+            FilterArg::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// Parses the command line, also returning the raw matches so argument order can be recovered.
+pub fn parse() -> (Cli, ArgMatches) {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    (cli, matches)
+}
+
+impl EditArgs {
+    /// Builds the list of operations in the order the flags were given.
+    pub fn operations(&self, matches: &ArgMatches) -> Vec<Operation> {
+        let mut positioned: Vec<(usize, Operation)> = Vec::new();
+        let mut collect = |id: &str, operations: Vec<Operation>| {
+            if let Some(indices) = matches.indices_of(id) {
+                positioned.extend(indices.zip(operations));
+            }
+        };
+
+        collect(
+            "crop",
+            self.crop.iter().copied().map(Operation::Crop).collect(),
+        );
+        collect(
+            "rotate",
+            self.rotate.iter().copied().map(Operation::Rotate).collect(),
+        );
+        collect(
+            "flip",
+            self.flip
+                .iter()
+                .map(|flip| match flip {
+                    FlipArg::Horizontal => Operation::Flip(Flip::Horizontal),
+                    FlipArg::Vertical => Operation::Flip(Flip::Vertical),
+                })
+                .collect(),
+        );
+        collect(
+            "resize",
+            self.resize
+                .iter()
+                .map(|&(width, height)| Operation::Resize {
+                    width,
+                    height,
+                    mode: self.resize_mode.into(),
+                    filter: self.filter.into(),
+                })
+                .collect(),
+        );
+        collect(
+            "brighten",
+            self.brighten
+                .iter()
+                .copied()
+                .map(Operation::Brighten)
+                .collect(),
+        );
+        collect(
+            "contrast",
+            self.contrast
+                .iter()
+                .copied()
+                .map(Operation::Contrast)
+                .collect(),
+        );
+        collect(
+            "hue",
+            self.hue.iter().copied().map(Operation::HueRotate).collect(),
+        );
+        if self.grayscale {
+            collect("grayscale", vec![Operation::Grayscale]);
+        }
+        if self.invert {
+            collect("invert", vec![Operation::Invert]);
+        }
+
+        positioned.sort_by_key(|(index, _)| *index);
+        positioned
+            .into_iter()
+            .map(|(_, operation)| operation)
+            .collect()
+    }
+}
+
+fn parse_crop(value: &str) -> Result<CropState, String> {
+    let parts = value
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    match parts[..] {
+        [left, right, top, bottom] => Ok(CropState {
+            left,
+            right,
+            top,
+            bottom,
+        }),
+        _ => Err("expected four comma separated margins".to_string()),
+    }
+}
+
+fn parse_rotation(value: &str) -> Result<Rotation, String> {
+    match value {
+        "90" => Ok(Rotation::Rotate90),
+        "180" => Ok(Rotation::Rotate180),
+        "270" | "-90" => Ok(Rotation::Rotate270),
+        _ => Err("expected 90, 180 or 270".to_string()),
+    }
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| "expected WIDTHxHEIGHT".to_string())?;
+    let width = width.parse::<u32>().map_err(|e| e.to_string())?;
+    let height = height.parse::<u32>().map_err(|e| e.to_string())?;
+    Ok((width, height))
+}
+
+fn parse_threshold(value: &str) -> Result<Threshold, String> {
+    match value {
+        "otsu" => Ok(Threshold::Otsu),
+        "dither" => Ok(Threshold::Dithered),
+        _ => value
+            .parse::<u8>()
+            .map(Threshold::Fixed)
+            .map_err(|_| "expected a number from 0 to 255, otsu or dither".to_string()),
+    }
+}
+
+fn parse_dot_color(value: &str) -> Result<DotColor, String> {
+    if value == "average" {
+        return Ok(DotColor::Average);
+    }
+    parse_hex_color(value)
+        .map(DotColor::Single)
+        .ok_or_else(|| "expected average or a hex color like #33ff66".to_string())
+}
+
+fn parse_background(value: &str) -> Result<BackgroundArg, String> {
+    match value {
+        "terminal" => Ok(BackgroundArg::Terminal),
+        "checkerboard" => Ok(BackgroundArg::Checkerboard),
+        _ => parse_hex_color(value)
+            .map(BackgroundArg::Color)
+            .ok_or_else(|| {
+                "expected terminal, checkerboard or a hex color like #1e1e2e".to_string()
+            }),
+    }
+}
+
+/// Parses `#rrggbb`, the `#` is optional.
+fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    // from_str_radix also takes a leading sign
+    if hex.len() != 6 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let [_, r, g, b] = u32::from_str_radix(hex, 16).ok()?.to_be_bytes();
+    Some([r, g, b])
+}
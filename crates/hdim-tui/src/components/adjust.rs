@@ -0,0 +1,62 @@
+use crate::app::App;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use hdim_core::{Adjustment, state::AdjustState};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListItem},
+};
+
+pub fn render_adjust_options<'a>(app: &'a App) -> List<'a> {
+    let state = &app.adjust_state;
+    let adjust_options = Adjustment::ALL
+        .into_iter()
+        .map(|adjustment| {
+            format!(
+                "{}: {}",
+                adjustment,
+                adjustment.format_value(state.get(adjustment))
+            )
+        })
+        .chain(["Reset".to_string(), "Apply adjustments".to_string()]);
+    let adjust_items: Vec<ListItem> = adjust_options
+        .enumerate()
+        .map(|(i, option)| {
+            let mut item = ListItem::new(option);
+            if app.selected_adjust_option_index == i {
+                item = item.style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+            item
+        })
+        .collect();
+
+    List::new(adjust_items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Adjust Options"),
+    )
+}
+
+pub fn handle_adjust_events(key: KeyEvent, app: &mut App) {
+    let selected = Adjustment::ALL
+        .get(app.selected_adjust_option_index)
+        .copied();
+    // Shift with an arrow changes the value in fine steps
+    let fine = key.modifiers.contains(KeyModifiers::SHIFT);
+    match (key.code, selected) {
+        (KeyCode::Tab, _) => {
+            app.selected_adjust_option_index = (app.selected_adjust_option_index + 1) % 8;
+        }
+        (KeyCode::Left, Some(adjustment)) => app.adjust_state.nudge(adjustment, -1, fine),
+        (KeyCode::Right, Some(adjustment)) => app.adjust_state.nudge(adjustment, 1, fine),
+        // Enter on a value puts it back to neutral
+        (KeyCode::Enter, Some(adjustment)) => {
+            let neutral = AdjustState::default().get(adjustment);
+            app.adjust_state.set(adjustment, neutral);
+        }
+        (KeyCode::Enter, None) if app.selected_adjust_option_index == 6 => {
+            app.adjust_state = AdjustState::default();
+        }
+        (KeyCode::Enter, None) => app.apply_adjustments(),
+        _ => {}
+    }
+}
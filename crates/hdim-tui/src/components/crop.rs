@@ -1,4 +1,5 @@
 use crate::app::{App, AppMode};
+use crate::components::digit_input::{DigitInput, handle_digit_input};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     prelude::*,
@@ -6,7 +7,14 @@ use ratatui::{
 };
 
 pub fn render_crop_options<'a>(app: &'a App) -> List<'a> {
-    let crop_options = ["Left", "Right", "Top", "Bottom", "Crop from viewport"];
+    let crop_options = [
+        "Left",
+        "Right",
+        "Top",
+        "Bottom",
+        "Crop from viewport",
+        "Apply crop",
+    ];
     let crop_items: Vec<ListItem> = crop_options
         .iter()
         .enumerate()
@@ -41,15 +49,9 @@ pub fn render_crop_options<'a>(app: &'a App) -> List<'a> {
 
 pub fn handle_crop_events(key: KeyEvent, app: &mut App) {
     match app.mode {
-        AppMode::EditingCropValue => match key.code {
-            KeyCode::Char(c) if c.is_ascii_digit() => {
-                app.crop_input.push(c);
-            }
-            KeyCode::Backspace => {
-                app.crop_input.pop();
-            }
-            KeyCode::Enter => {
-                if let Ok(value) = app.crop_input.parse::<u32>() {
+        AppMode::EditingCropValue => {
+            if let DigitInput::Done(value) = handle_digit_input(key, &mut app.crop_input) {
+                if let Some(value) = value {
                     match app.selected_crop_option_index {
                         0 => app.crop_state.left = value,
                         1 => app.crop_state.right = value,
@@ -57,29 +59,25 @@ pub fn handle_crop_events(key: KeyEvent, app: &mut App) {
                         3 => app.crop_state.bottom = value,
                         _ => {}
                     }
+                    app.update_display_image();
                 }
-                app.crop_input.clear();
-                app.mode = AppMode::Normal;
-            }
-            KeyCode::Esc => {
-                app.crop_input.clear();
                 app.mode = AppMode::Normal;
             }
-            _ => {}
-        },
+        }
         AppMode::Normal => match key.code {
             KeyCode::Tab => {
-                app.selected_crop_option_index = (app.selected_crop_option_index + 1) % 5;
+                app.selected_crop_option_index = (app.selected_crop_option_index + 1) % 6;
             }
-            KeyCode::Enter => {
-                if app.selected_crop_option_index < 4 {
-                    app.mode = AppMode::EditingCropValue;
-                } else {
-                    // TODO: "Crop from viewport" logic
-                }
+            KeyCode::Enter if app.selected_crop_option_index < 4 => {
+                app.mode = AppMode::EditingCropValue;
             }
+            KeyCode::Enter if app.selected_crop_option_index == 4 => app.crop_to_viewport(),
+            KeyCode::Enter => app.apply_crop(),
             _ => {}
         },
-        AppMode::ExifView => {} // Do nothing in ExifView mode
+        AppMode::EditingTransformValue
+        | AppMode::EditingResizeValue
+        | AppMode::ExifView
+        | AppMode::SavingAs => {} // Handled in events.rs
     }
 }
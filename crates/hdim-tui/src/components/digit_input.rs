@@ -0,0 +1,31 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// What a key press did to a whole number being typed.
+pub enum DigitInput {
+    /// The number is still being typed.
+    Editing,
+    /// Typing ended, with the number if Enter confirmed a valid one.
+    Done(Option<u32>),
+}
+
+/// Handles a key press while a whole number is typed into `input`.
+///
+/// Digits are appended, Backspace removes the last one, Enter confirms and
+/// Esc cancels. `input` is cleared once typing ends.
+pub fn handle_digit_input(key: KeyEvent, input: &mut String) -> DigitInput {
+    let value = match key.code {
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            input.push(c);
+            return DigitInput::Editing;
+        }
+        KeyCode::Backspace => {
+            input.pop();
+            return DigitInput::Editing;
+        }
+        KeyCode::Enter => input.parse::<u32>().ok(),
+        KeyCode::Esc => None,
+        _ => return DigitInput::Editing,
+    };
+    input.clear();
+    DigitInput::Done(value)
+}
@@ -13,10 +13,10 @@ impl ExifView {
     pub fn new(exif_data: &ExifData) -> Self {
         let mut items = Vec::new();
         items.push(ListItem::new("General:").style(Style::default().add_modifier(Modifier::BOLD)));
-        if let Some(datetime) = &exif_data.datetime {
-            if let Some(original) = &datetime.original {
-                items.push(ListItem::new(format!("  Date Time: {}", original)));
-            }
+        if let Some(datetime) = &exif_data.datetime
+            && let Some(original) = &datetime.original
+        {
+            items.push(ListItem::new(format!("  Date Time: {}", original)));
         }
 
         if let Some(camera) = &exif_data.camera {
@@ -0,0 +1,95 @@
+use crate::app::{App, HistogramSource};
+use hdim_core::{Channel, Histogram};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Sparkline},
+};
+
+/// Rows of the histogram panel, borders included.
+pub const HISTOGRAM_HEIGHT: u16 = 10;
+
+/// Columns of the statistics next to the histograms.
+const STATS_WIDTH: u16 = 36;
+
+pub fn render_histogram(frame: &mut Frame, area: Rect, app: &mut App) {
+    let mut title = match app.histogram_source {
+        Some(HistogramSource::Whole) => "Histogram - whole image".to_string(),
+        _ => "Histogram - crop".to_string(),
+    };
+    if !app.adjust_state.is_neutral() {
+        title.push_str(" (pending adjustments)");
+    }
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let Some(histogram) = app.histogram() else {
+        return;
+    };
+
+    let [graphs_area, stats_area] =
+        Layout::horizontal([Constraint::Min(0), Constraint::Length(STATS_WIDTH)]).areas(inner);
+    let rows = Layout::vertical([Constraint::Ratio(1, 4); 4]).split(graphs_area);
+    for (channel, row) in Channel::ALL.into_iter().zip(rows.iter()) {
+        let sparkline = Sparkline::default()
+            .data(columns(histogram, channel, row.width))
+            .style(Style::default().fg(channel_color(channel)));
+        frame.render_widget(sparkline, *row);
+    }
+
+    frame.render_widget(Paragraph::new(stats_lines(histogram)), stats_area);
+}
+
+/// The bins of `channel` merged or repeated to `width` columns.
+///
+/// Counts are shown by their square root, so that a few clipped pixels do
+/// not flatten the rest of the histogram.
+fn columns(histogram: &Histogram, channel: Channel, width: u16) -> Vec<u64> {
+    let bins = histogram.bins(channel);
+    let width = width.max(1) as usize;
+    (0..width)
+        .map(|column| {
+            let start = column * bins.len() / width;
+            let end = ((column + 1) * bins.len() / width).max(start + 1);
+            let count: u64 = bins[start..end].iter().sum();
+            (count as f64).sqrt().round() as u64
+        })
+        .collect()
+}
+
+fn channel_color(channel: Channel) -> Color {
+    match channel {
+        Channel::Red => Color::Red,
+        Channel::Green => Color::Green,
+        Channel::Blue => Color::Blue,
+        Channel::Luminance => Color::White,
+    }
+}
+
+/// A table of the statistics of every channel, with the clipped percentages last.
+fn stats_lines(histogram: &Histogram) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(format!(
+        "{:<2}{:>4}{:>4}{:>7}{:>4}{:>7}{:>7}",
+        "", "min", "max", "mean", "med", "dark", "light"
+    ))];
+    for channel in Channel::ALL {
+        let line = match histogram.stats(channel) {
+            Some(stats) => format!(
+                "{:<2}{:>4}{:>4}{:>7.1}{:>4}{:>6.1}%{:>6.1}%",
+                channel,
+                stats.min,
+                stats.max,
+                stats.mean,
+                stats.median,
+                stats.clipped_shadows,
+                stats.clipped_highlights
+            ),
+            None => format!("{:<2}no visible pixels", channel),
+        };
+        lines.push(Line::styled(
+            line,
+            Style::default().fg(channel_color(channel)),
+        ));
+    }
+    lines.push(Line::from(format!("{} pixels", histogram.pixels)));
+    lines
+}
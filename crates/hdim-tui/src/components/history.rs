@@ -0,0 +1,24 @@
+use crate::app::App;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListItem},
+};
+
+/// Lists the edit history, oldest first. Undone edits that can be redone are dimmed.
+pub fn render_history<'a>(app: &'a App) -> List<'a> {
+    let applied = app
+        .pipeline
+        .operations()
+        .iter()
+        .map(|operation| ListItem::new(operation.to_string()));
+    let undone = app.pipeline.undone().iter().map(|operation| {
+        ListItem::new(operation.to_string()).style(Style::default().fg(Color::DarkGray))
+    });
+
+    let mut items: Vec<ListItem> = applied.chain(undone).collect();
+    if items.is_empty() {
+        items.push(ListItem::new("No edits yet"));
+    }
+
+    List::new(items).block(Block::default().borders(Borders::ALL).title("History"))
+}
@@ -1,2 +1,8 @@
+pub mod adjust;
 pub mod crop;
+pub mod digit_input;
 pub mod exif_view;
+pub mod histogram;
+pub mod history;
+pub mod resize;
+pub mod transform;
@@ -0,0 +1,101 @@
+use crate::app::{App, AppMode};
+use crate::components::digit_input::{DigitInput, handle_digit_input};
+use crossterm::event::{KeyCode, KeyEvent};
+use hdim_core::resize::{MAX_PIXELS, filter_name};
+use hdim_core::state::ResizeState;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListItem},
+};
+
+pub fn render_resize_options<'a>(app: &'a App) -> List<'a> {
+    let state = &app.resize_state;
+    let source = app.resize_source();
+    let output = state.output_size(source);
+    let resize_options = [
+        format!("Width: {}", state.width),
+        format!("Height: {}", state.height),
+        format!("Scale: {:.0}%", state.percent(source)),
+        format!("Mode: {}", state.mode),
+        format!(
+            "Lock aspect: {}",
+            if state.lock_aspect { "on" } else { "off" }
+        ),
+        format!("Filter {}", filter_name(state.filter)),
+        format!("Apply {}x{}", output.width, output.height),
+    ];
+    let resize_items: Vec<ListItem> = resize_options
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut text)| {
+            if app.mode == AppMode::EditingResizeValue && app.selected_resize_option_index == i {
+                text.push_str(&format!(" {}", app.resize_input));
+            }
+
+            let mut item = ListItem::new(text);
+            if app.selected_resize_option_index == i {
+                item = item.style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+            item
+        })
+        .collect();
+
+    List::new(resize_items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Resize Options"),
+    )
+}
+
+pub fn handle_resize_events(key: KeyEvent, app: &mut App) {
+    match app.mode {
+        AppMode::EditingResizeValue => {
+            if let DigitInput::Done(value) = handle_digit_input(key, &mut app.resize_input) {
+                // An empty image is never a useful target
+                if let Some(value) = value.filter(|&value| value > 0) {
+                    let source = app.resize_source();
+                    let mut state = app.resize_state;
+                    match app.selected_resize_option_index {
+                        0 => state.set_width(value, source),
+                        1 => state.set_height(value, source),
+                        2 => state.set_percent(value, source),
+                        _ => {}
+                    }
+                    if state.fits_limit(source) {
+                        app.resize_state = state;
+                    } else {
+                        app.status = Some(resize_limit_message(&state));
+                    }
+                }
+                app.mode = AppMode::Normal;
+            }
+        }
+        AppMode::Normal => match key.code {
+            KeyCode::Tab => {
+                app.selected_resize_option_index = (app.selected_resize_option_index + 1) % 7;
+            }
+            KeyCode::Enter => match app.selected_resize_option_index {
+                0..=2 => app.mode = AppMode::EditingResizeValue,
+                3 => app.resize_state.cycle_mode(),
+                4 => app.resize_state.lock_aspect = !app.resize_state.lock_aspect,
+                5 => app.resize_state.cycle_filter(),
+                _ => app.apply_resize(),
+            },
+            _ => {}
+        },
+        AppMode::EditingCropValue
+        | AppMode::EditingTransformValue
+        | AppMode::ExifView
+        | AppMode::SavingAs => {} // Handled in events.rs
+    }
+}
+
+/// Explains why a resize to `state` is not applied.
+pub fn resize_limit_message(state: &ResizeState) -> String {
+    format!(
+        "{}x{} is too large, resizes are limited to {} megapixels",
+        state.width,
+        state.height,
+        MAX_PIXELS / 1_000_000
+    )
+}
@@ -0,0 +1,107 @@
+use crate::app::{App, AppMode};
+use crossterm::event::{KeyCode, KeyEvent};
+use hdim_core::{Fill, Flip, Interpolation, Operation, Rotation};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListItem},
+};
+
+pub fn render_transform_options<'a>(app: &'a App) -> List<'a> {
+    let state = &app.transform_state;
+    let mut angle = format!("Angle: {}°", state.degrees);
+    if app.mode == AppMode::EditingTransformValue {
+        angle.push_str(&format!(" {}", app.transform_input));
+    }
+    let transform_options = [
+        "Rotate 90°".to_string(),
+        "Rotate 180°".to_string(),
+        "Rotate 270°".to_string(),
+        "Flip horizontal".to_string(),
+        "Flip vertical".to_string(),
+        angle,
+        format!("Interp: {}", state.interpolation),
+        format!("Fill: {}", state.fill),
+        "Apply rotation".to_string(),
+    ];
+    let transform_items: Vec<ListItem> = transform_options
+        .into_iter()
+        .enumerate()
+        .map(|(i, option)| {
+            let mut item = ListItem::new(option);
+            if app.selected_transform_option_index == i {
+                item = item.style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+            item
+        })
+        .collect();
+
+    List::new(transform_items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Transform Options"),
+    )
+}
+
+pub fn handle_transform_events(key: KeyEvent, app: &mut App) {
+    match app.mode {
+        AppMode::EditingTransformValue => match key.code {
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '-' || c == '.' => {
+                app.transform_input.push(c);
+            }
+            KeyCode::Backspace => {
+                app.transform_input.pop();
+            }
+            KeyCode::Enter => {
+                if let Ok(degrees) = app.transform_input.parse::<f32>() {
+                    // Whole turns do nothing
+                    app.transform_state.degrees = degrees % 360.0;
+                    app.update_display_image();
+                }
+                app.transform_input.clear();
+                app.mode = AppMode::Normal;
+            }
+            KeyCode::Esc => {
+                app.transform_input.clear();
+                app.mode = AppMode::Normal;
+            }
+            _ => {}
+        },
+        AppMode::Normal => match key.code {
+            KeyCode::Tab => {
+                app.selected_transform_option_index = (app.selected_transform_option_index + 1) % 9;
+            }
+            KeyCode::Enter => match app.selected_transform_option_index {
+                0 => app.push_operation(Operation::Rotate(Rotation::Rotate90)),
+                1 => app.push_operation(Operation::Rotate(Rotation::Rotate180)),
+                2 => app.push_operation(Operation::Rotate(Rotation::Rotate270)),
+                3 => app.push_operation(Operation::Flip(Flip::Horizontal)),
+                4 => app.push_operation(Operation::Flip(Flip::Vertical)),
+                5 => app.mode = AppMode::EditingTransformValue,
+                6 => {
+                    let state = &mut app.transform_state;
+                    state.interpolation = match state.interpolation {
+                        Interpolation::Nearest => Interpolation::Bilinear,
+                        Interpolation::Bilinear => Interpolation::Bicubic,
+                        Interpolation::Bicubic => Interpolation::Nearest,
+                    };
+                    app.update_display_image();
+                }
+                7 => {
+                    let state = &mut app.transform_state;
+                    state.fill = match state.fill {
+                        Fill::Transparent => Fill::Color([0, 0, 0, 255]),
+                        Fill::Color([0, 0, 0, 255]) => Fill::Color([255, 255, 255, 255]),
+                        Fill::Color(_) => Fill::Transparent,
+                    };
+                    app.update_display_image();
+                }
+                _ => app.apply_rotation(),
+            },
+            _ => {}
+        },
+        AppMode::EditingCropValue
+        | AppMode::EditingResizeValue
+        | AppMode::ExifView
+        | AppMode::SavingAs => {} // Handled in events.rs
+    }
+}
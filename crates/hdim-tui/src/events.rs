@@ -1,5 +1,8 @@
 use crate::app::{ActiveWidget, App, AppMode};
+use crate::components::adjust::handle_adjust_events;
 use crate::components::crop::handle_crop_events;
+use crate::components::resize::handle_resize_events;
+use crate::components::transform::handle_transform_events;
 use color_eyre::eyre::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 use hdim_core::state::Tool;
@@ -10,23 +13,26 @@ const ZOOM_FACTOR: f32 = 1.2; // Zoom factor per key press
 
 pub fn handle_events(app: &mut App) -> Result<bool> {
     if event::poll(Duration::from_millis(16))? {
+        // Anything from a key to a resize may change the screen
+        app.redraw = true;
         if app.last_input_time.elapsed() >= app.input_delay {
             let mut last_key_event = None;
             // Drain the event queue, only keeping the last key press event
             while event::poll(Duration::from_millis(0))? {
-                if let Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
-                        last_key_event = Some(key);
-                    }
+                if let Event::Key(key) = event::read()?
+                    && key.kind == KeyEventKind::Press
+                {
+                    last_key_event = Some(key);
                 }
             }
 
             if let Some(key) = last_key_event {
                 app.last_input_time = Instant::now();
-                handle_key_press(app, key);
-                if key.code == KeyCode::Char('q') {
+                // 'q' is a regular character while typing a path
+                if key.code == KeyCode::Char('q') && app.mode != AppMode::SavingAs {
                     return Ok(true);
                 }
+                handle_key_press(app, key);
             }
         } else {
             // If the input delay hasn't passed, drain the queue to prevent event buildup
@@ -39,7 +45,8 @@ pub fn handle_events(app: &mut App) -> Result<bool> {
 }
 
 fn handle_key_press(app: &mut App, key: KeyEvent) {
-    let pan_amount_pixels = (PAN_AMOUNT_CHARACTERS as f32 * app.zoom).round() as i32;
+    let (pan_x, pan_y) = app.pan_step((PAN_AMOUNT_CHARACTERS as f32 * app.zoom).round() as u32);
+    let (pan_x, pan_y) = (pan_x as i32, pan_y as i32);
 
     match app.mode {
         AppMode::ExifView => match key.code {
@@ -54,33 +61,27 @@ fn handle_key_press(app: &mut App, key: KeyEvent) {
                 }
             }
             KeyCode::Esc => {
+                if let Some(exif_view) = app.exif_view.as_mut() {
+                    exif_view.unselect();
+                }
                 app.mode = AppMode::Normal;
                 app.active_widget = ActiveWidget::Main;
             }
             _ => {}
         },
-        AppMode::EditingCropValue => match key.code {
-            KeyCode::Char(c) if c.is_ascii_digit() => {
-                app.crop_input.push(c);
+        AppMode::EditingCropValue => handle_crop_events(key, app),
+        AppMode::EditingTransformValue => handle_transform_events(key, app),
+        AppMode::EditingResizeValue => handle_resize_events(key, app),
+        AppMode::SavingAs => match key.code {
+            KeyCode::Char(c) => {
+                app.save_input.push(c);
             }
             KeyCode::Backspace => {
-                app.crop_input.pop();
-            }
-            KeyCode::Enter => {
-                if let Ok(value) = app.crop_input.parse::<u32>() {
-                    match app.selected_crop_option_index {
-                        0 => app.crop_state.left = value,
-                        1 => app.crop_state.right = value,
-                        2 => app.crop_state.top = value,
-                        3 => app.crop_state.bottom = value,
-                        _ => {}
-                    }
-                }
-                app.crop_input.clear();
-                app.mode = AppMode::Normal;
+                app.save_input.pop();
             }
+            KeyCode::Enter => app.save_as(),
             KeyCode::Esc => {
-                app.crop_input.clear();
+                app.save_input.clear();
                 app.mode = AppMode::Normal;
             }
             _ => {}
@@ -93,6 +94,18 @@ fn handle_key_press(app: &mut App, key: KeyEvent) {
                 app.selected_tool = Some(Tool::Crop);
                 app.active_widget = ActiveWidget::RightToolbar;
             }
+            KeyCode::Char('3') => {
+                app.selected_tool = Some(Tool::Transform);
+                app.active_widget = ActiveWidget::RightToolbar;
+            }
+            KeyCode::Char('4') => {
+                app.begin_resize();
+                app.active_widget = ActiveWidget::RightToolbar;
+            }
+            KeyCode::Char('5') => {
+                app.selected_tool = Some(Tool::Adjust);
+                app.active_widget = ActiveWidget::RightToolbar;
+            }
             KeyCode::Char('2') => {
                 app.selected_tool = Some(Tool::Exif);
                 app.mode = AppMode::ExifView;
@@ -101,6 +114,12 @@ fn handle_key_press(app: &mut App, key: KeyEvent) {
                     exif_view.state.select(Some(0));
                 }
             }
+            KeyCode::Char('s') => app.begin_save_as(),
+            KeyCode::Char('u') => app.undo(),
+            KeyCode::Char('r') => app.redo(),
+            KeyCode::Char('b') => app.cycle_background(),
+            KeyCode::Char('o') => app.toggle_orientation(),
+            KeyCode::Char('h') => app.cycle_histogram(),
             KeyCode::Esc => {
                 app.selected_tool = None;
                 app.active_widget = ActiveWidget::Main;
@@ -108,17 +127,20 @@ fn handle_key_press(app: &mut App, key: KeyEvent) {
             _ => {
                 if let Some(Tool::Crop) = app.selected_tool {
                     handle_crop_events(key, app);
-                } else {
-                    match app.active_widget {
-                        ActiveWidget::Main => match key.code {
-                            KeyCode::Up => app.scroll(0, -pan_amount_pixels),
-                            KeyCode::Down => app.scroll(0, pan_amount_pixels),
-                            KeyCode::Left => app.scroll(-pan_amount_pixels, 0),
-                            KeyCode::Right => app.scroll(pan_amount_pixels, 0),
-                            KeyCode::PageUp => app.zoom(1.0 / ZOOM_FACTOR),
-                            KeyCode::PageDown => app.zoom(ZOOM_FACTOR),
-                            _ => {}
-                        },
+                } else if let Some(Tool::Transform) = app.selected_tool {
+                    handle_transform_events(key, app);
+                } else if let Some(Tool::Resize) = app.selected_tool {
+                    handle_resize_events(key, app);
+                } else if let Some(Tool::Adjust) = app.selected_tool {
+                    handle_adjust_events(key, app);
+                } else if app.active_widget == ActiveWidget::Main {
+                    match key.code {
+                        KeyCode::Up => app.scroll(0, -pan_y),
+                        KeyCode::Down => app.scroll(0, pan_y),
+                        KeyCode::Left => app.scroll(-pan_x, 0),
+                        KeyCode::Right => app.scroll(pan_x, 0),
+                        KeyCode::PageUp => app.zoom(1.0 / ZOOM_FACTOR),
+                        KeyCode::PageDown => app.zoom(ZOOM_FACTOR),
                         _ => {}
                     }
                 }
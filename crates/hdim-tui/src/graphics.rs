@@ -0,0 +1,234 @@
+use crate::cli::{
+    AveragingArg, BackendArg, BackgroundArg, DitherArg, GlyphsArg, RenderArgs, ZoomFilterArg,
+};
+use color_eyre::eyre::{Result, eyre};
+use crossterm::{
+    cursor::{MoveTo, RestorePosition, SavePosition},
+    queue,
+    style::Print,
+    terminal,
+};
+use hdim_core::HdimImage;
+use hdim_render::{
+    Ascii, AsciiOptions, Backend, BackendKind, Background, BrailleOptions, CellRenderer,
+    ColorDepth, ColorSpace, Dither, Filter, Glyphs, HalfBlock, Iterm, Kitty, RenderOptions, Sixel,
+    View, detect,
+};
+use ratatui::layout::Rect;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Cell size assumed when the terminal does not report its size in pixels.
+const FALLBACK_CELL_SIZE: (u32, u32) = (10, 20);
+/// How long to wait for the terminal to answer the capability queries.
+const DETECT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Draws the main viewport with a pixel graphics backend such as Sixel.
+///
+/// Graphics escape sequences cannot live in ratatui's cell buffer, so they are
+/// written straight to the terminal after each frame, on top of the blank
+/// area `ui::render` leaves for them.
+pub struct GraphicsViewport {
+    backend: Box<dyn Backend>,
+    /// The screen area of the image, set by `ui::render`
+    pub area: Rect,
+    /// The view of the image to draw, set by `ui::render`
+    pub view: Option<View>,
+    /// The view and image generation that are currently on screen
+    drawn: Option<(Rect, View, u64)>,
+}
+
+impl GraphicsViewport {
+    pub fn new(backend: Box<dyn Backend>) -> Self {
+        Self {
+            backend,
+            area: Rect::default(),
+            view: None,
+            drawn: None,
+        }
+    }
+
+    /// Writes the image to the terminal if the view or the image changed since the last draw.
+    pub fn draw(&mut self, image: &HdimImage, generation: u64) -> Result<()> {
+        let Some(view) = self.view else {
+            return Ok(());
+        };
+        let key = (self.area, view, generation);
+        if self.drawn == Some(key) || self.area.is_empty() {
+            return Ok(());
+        }
+
+        let output = self
+            .backend
+            .render(&image.data, &view)
+            .map_err(|e| eyre!("{}", e))?;
+        let mut stdout = io::stdout().lock();
+        queue!(stdout, SavePosition)?;
+        // Clear what the previous image left behind, ratatui believes these cells are blank
+        let blank = " ".repeat(self.area.width as usize);
+        for row in self.area.top()..self.area.bottom() {
+            queue!(stdout, MoveTo(self.area.x, row), Print(&blank))?;
+        }
+        queue!(
+            stdout,
+            MoveTo(self.area.x, self.area.y),
+            Print(output),
+            RestorePosition
+        )?;
+        stdout.flush()?;
+
+        self.drawn = Some(key);
+        Ok(())
+    }
+
+    /// Removes the image from the terminal, for backends that keep images apart from the text.
+    pub fn clear(&mut self) -> Result<()> {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(self.backend.clear().as_bytes())?;
+        stdout.flush()?;
+        self.drawn = None;
+        Ok(())
+    }
+}
+
+/// Resolves `--backend`, querying the terminal for `auto`.
+pub fn backend_kind(args: &RenderArgs) -> BackendKind {
+    match args.backend {
+        BackendArg::Auto => detect::detect(DETECT_TIMEOUT),
+        BackendArg::Kitty => BackendKind::Kitty,
+        BackendArg::Sixel => BackendKind::Sixel,
+        BackendArg::Iterm => BackendKind::Iterm,
+        BackendArg::Truecolor => BackendKind::TrueColor,
+        BackendArg::Ansi256 => BackendKind::Ansi256,
+        BackendArg::Ansi16 => BackendKind::Ansi16,
+        BackendArg::Ascii => BackendKind::Ascii,
+    }
+}
+
+/// Whether the backend draws pixels outside of the character grid.
+pub fn is_graphics(kind: BackendKind) -> bool {
+    matches!(
+        kind,
+        BackendKind::Kitty | BackendKind::Sixel | BackendKind::Iterm
+    )
+}
+
+/// Asks the terminal for its background color, black if it does not answer.
+pub fn terminal_background() -> [u8; 3] {
+    detect::background(DETECT_TIMEOUT).unwrap_or([0, 0, 0])
+}
+
+/// The background chosen by `--background`, with `terminal` being the given color.
+pub fn background(arg: BackgroundArg, terminal: [u8; 3]) -> Background {
+    match arg {
+        BackgroundArg::Terminal => Background::Color(terminal),
+        BackgroundArg::Checkerboard => Background::Checkerboard,
+        BackgroundArg::Color(color) => Background::Color(color),
+    }
+}
+
+/// The backgrounds the viewport cycles through, starting with the one chosen by `--background`.
+pub fn backgrounds(arg: BackgroundArg, terminal: [u8; 3]) -> Vec<Background> {
+    let mut backgrounds = vec![background(arg, terminal)];
+    for candidate in [
+        Background::Color(terminal),
+        Background::Checkerboard,
+        Background::Color([0, 0, 0]),
+        Background::Color([255, 255, 255]),
+    ] {
+        if !backgrounds.contains(&candidate) {
+            backgrounds.push(candidate);
+        }
+    }
+    backgrounds
+}
+
+/// Creates the renderer for a backend kind.
+pub fn create_backend(
+    kind: BackendKind,
+    args: &RenderArgs,
+    background: Background,
+) -> Box<dyn Backend> {
+    match kind {
+        BackendKind::Kitty => Box::new(Kitty::default()),
+        BackendKind::Sixel => {
+            let (cell_width, cell_height) = cell_size();
+            Box::new(Sixel {
+                colors: args.sixel_colors,
+                cell_width,
+                cell_height,
+            })
+        }
+        BackendKind::Iterm => Box::new(Iterm),
+        _ => text_renderer(kind, args, background),
+    }
+}
+
+/// Creates the character renderer for a backend kind, truecolor half blocks for graphics kinds.
+pub fn text_renderer(
+    kind: BackendKind,
+    args: &RenderArgs,
+    background: Background,
+) -> Box<dyn CellRenderer> {
+    match kind {
+        BackendKind::Ascii => Box::new(Ascii::new(AsciiOptions {
+            ramp: args.ramp.clone(),
+            edges: args.edges,
+        })),
+        _ => Box::new(HalfBlock::new(render_options(kind, args, background))),
+    }
+}
+
+/// The half-block render options for a backend kind.
+pub fn render_options(
+    kind: BackendKind,
+    args: &RenderArgs,
+    background: Background,
+) -> RenderOptions {
+    RenderOptions {
+        glyphs: match args.glyphs {
+            GlyphsArg::HalfBlock => Glyphs::HalfBlock,
+            GlyphsArg::Quadrant => Glyphs::Quadrant,
+            GlyphsArg::Sextant => Glyphs::Sextant,
+            GlyphsArg::Octant => Glyphs::Octant,
+            GlyphsArg::Braille => Glyphs::Braille,
+        },
+        filter: match args.zoom_filter {
+            ZoomFilterArg::Box => Filter::Box,
+            ZoomFilterArg::Lanczos => Filter::Lanczos3,
+            ZoomFilterArg::Mitchell => Filter::Mitchell,
+        },
+        averaging: match args.averaging {
+            AveragingArg::Srgb => ColorSpace::Srgb,
+            AveragingArg::Linear => ColorSpace::Linear,
+            AveragingArg::Oklab => ColorSpace::Oklab,
+        },
+        background,
+        color_depth: match kind {
+            BackendKind::Ansi256 => ColorDepth::Ansi256,
+            BackendKind::Ansi16 => ColorDepth::Ansi16,
+            _ => ColorDepth::TrueColor,
+        },
+        dither: match args.dither {
+            DitherArg::None => Dither::None,
+            DitherArg::FloydSteinberg => Dither::FloydSteinberg,
+            DitherArg::Ordered => Dither::Ordered,
+        },
+        braille: BrailleOptions {
+            threshold: args.threshold,
+            color: args.dot_color,
+            invert: args.invert_dots,
+        },
+    }
+}
+
+/// Returns the size of a terminal cell in pixels, as (width, height).
+pub fn cell_size() -> (u32, u32) {
+    match terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns) as u32,
+            (size.height / size.rows) as u32,
+        ),
+        _ => FALLBACK_CELL_SIZE,
+    }
+}
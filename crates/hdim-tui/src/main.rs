@@ -1,44 +1,102 @@
 mod app;
+mod batch;
+mod cli;
 mod components;
 mod events;
+mod graphics;
+mod print;
 mod ui;
 use app::App;
 use color_eyre::eyre::{Result, eyre};
-use hdim_core::HdimImage;
+use hdim_core::{HdimImage, Pyramid};
 use ratatui::DefaultTerminal;
-use std::env;
-use std::path::PathBuf;
+use std::path::Path;
 
+use crate::cli::{Cli, Command, ShowArgs};
 use crate::events::handle_events;
+use crate::graphics::GraphicsViewport;
 use crate::ui::render;
 
 fn main() -> Result<()> {
     color_eyre::install()?;
-    let terminal = ratatui::init();
+    let (cli, matches) = cli::parse();
 
-    let image_path_str = env::args()
-        .nth(1)
-        .ok_or_else(|| eyre!("No image path provided. Usage: hdim <path/to/image>"))?;
-    let image_path = PathBuf::from(image_path_str);
+    match &cli.command {
+        Some(Command::Edit(args)) => {
+            let edit_matches = matches
+                .subcommand_matches("edit")
+                .expect("edit subcommand was parsed");
+            std::process::exit(batch::run(args, edit_matches));
+        }
+        Some(Command::Show(args)) => print::run(args),
+        None => match &cli.path {
+            Some(path) if cli.print => print::run(&ShowArgs {
+                path: path.clone(),
+                width: None,
+                height: None,
+                raw_orientation: cli.raw_orientation,
+                render: cli.render.clone(),
+            }),
+            Some(path) => run_tui(path, &cli),
+            None => Err(eyre!(
+                "No image path provided. Usage: hdim <path/to/image> or hdim edit --help"
+            )),
+        },
+    }
+}
 
-    let hdim_image =
-        HdimImage::from_path(&image_path).map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+fn run_tui(image_path: &Path, cli: &Cli) -> Result<()> {
+    let mut hdim_image = if cli.raw_orientation {
+        HdimImage::from_path_raw(image_path)
+    } else {
+        HdimImage::from_path(image_path)
+    }
+    .map_err(|e| color_eyre::eyre::eyre!("{}", e))?;
+    hdim_image.pyramid = Pyramid::with_budget(cli.pyramid_memory.saturating_mul(1024 * 1024));
 
     // Start with a zoom level that fits the image width to a default 100-column view
     let initial_zoom = hdim_image.width as f32 / 100.0;
 
-    let app = App::new(hdim_image, initial_zoom)?;
-    let result = run(terminal, app);
+    let kind = graphics::backend_kind(&cli.render);
+    let backgrounds = graphics::backgrounds(cli.render.background, graphics::terminal_background());
+    let graphics = graphics::is_graphics(kind).then(|| {
+        GraphicsViewport::new(graphics::create_backend(kind, &cli.render, backgrounds[0]))
+    });
 
+    let mut app = App::new(
+        hdim_image,
+        initial_zoom,
+        graphics,
+        kind,
+        cli.render.clone(),
+        backgrounds,
+    )?;
+    let terminal = ratatui::init();
+    let result = run(terminal, &mut app);
+
+    if let Some(graphics) = &mut app.graphics {
+        let _ = graphics.clear();
+    }
     ratatui::restore();
     result
 }
 
-fn run(mut terminal: DefaultTerminal, mut app: App) -> Result<()> {
+fn run(mut terminal: DefaultTerminal, app: &mut App) -> Result<()> {
     loop {
-        terminal.draw(|frame| render(frame, &mut app))?;
+        // Nothing changes on screen without an event, so idle loops only poll
+        if app.redraw {
+            terminal.draw(|frame| render(frame, app))?;
+            if let Some(graphics) = &mut app.graphics {
+                let image = match &app.adjust_preview {
+                    Some(preview) => &preview.image,
+                    None => &app.display_image,
+                };
+                graphics.draw(image, app.display_generation)?;
+            }
+            app.redraw = false;
+        }
 
-        if handle_events(&mut app)? {
+        if handle_events(app)? {
             break;
         }
     }
@@ -0,0 +1,75 @@
+use crate::cli::{BackendArg, BackgroundArg, ShowArgs};
+use crate::graphics;
+use color_eyre::eyre::{Result, eyre};
+use crossterm::terminal;
+use hdim_core::HdimImage;
+use hdim_render::{BackendKind, View};
+use std::io::{self, IsTerminal, Write};
+
+/// Width used when neither `--width` nor the terminal size is available.
+const DEFAULT_COLUMNS: u32 = 80;
+
+/// Writes the rendering of an image to stdout, sized to the terminal or the given bounds.
+pub fn run(args: &ShowArgs) -> Result<()> {
+    let hdim_image = if args.raw_orientation {
+        HdimImage::from_path_raw(&args.path)
+    } else {
+        HdimImage::from_path(&args.path)
+    }
+    .map_err(|e| eyre!("{}", e))?;
+    let view = fit_view(&hdim_image, args);
+    // There is no terminal to ask when the output is piped
+    let kind = if args.render.backend == BackendArg::Auto && !io::stdout().is_terminal() {
+        BackendKind::TrueColor
+    } else {
+        graphics::backend_kind(&args.render)
+    };
+    // Piped output may be shown on another terminal, assume a black one
+    let terminal =
+        if args.render.background == BackgroundArg::Terminal && io::stdout().is_terminal() {
+            graphics::terminal_background()
+        } else {
+            [0, 0, 0]
+        };
+    let background = graphics::background(args.render.background, terminal);
+    let mut output = graphics::create_backend(kind, &args.render, background)
+        .render(&hdim_image.data, &view)
+        .map_err(|e| eyre!("{}", e))?;
+    if graphics::is_graphics(kind) {
+        // Move the prompt below the image
+        output.push('\n');
+    }
+
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(output.as_bytes())
+        .and_then(|()| stdout.flush())
+    {
+        // The reader went away, e.g. `hdim show img.png | head`
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Fits the image into the requested size, or into the terminal without upscaling.
+fn fit_view(hdim_image: &HdimImage, args: &ShowArgs) -> View {
+    let terminal_size = if io::stdout().is_terminal() {
+        terminal::size().ok()
+    } else {
+        None
+    };
+
+    let natural_columns = hdim_image.width;
+    let natural_rows = hdim_image.height.div_ceil(2);
+    let columns = args.width.unwrap_or_else(|| match terminal_size {
+        Some((columns, _)) => (columns as u32).min(natural_columns),
+        None => DEFAULT_COLUMNS.min(natural_columns),
+    });
+    // Leave one row for the shell prompt
+    let rows = args.height.unwrap_or_else(|| match terminal_size {
+        Some((_, rows)) => (rows.saturating_sub(1) as u32).min(natural_rows),
+        None => natural_rows,
+    });
+
+    View::fit(hdim_image.width, hdim_image.height, columns, rows)
+}
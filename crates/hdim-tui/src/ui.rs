@@ -1,9 +1,14 @@
 use crate::app::{App, AppMode};
+use crate::components::adjust::render_adjust_options;
 use crate::components::crop::render_crop_options;
-use ansi_to_tui::IntoText;
+use crate::components::histogram::{HISTOGRAM_HEIGHT, render_histogram};
+use crate::components::history::render_history;
+use crate::components::resize::render_resize_options;
+use crate::components::transform::render_transform_options;
+use crate::graphics;
 use color_eyre::eyre::Result;
-use hdim_core::state::Tool;
-use hdim_render::view::View;
+use hdim_core::{Size, state::Tool, viewport_source_size};
+use hdim_render::{ImageWidget, view::View};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, Paragraph},
@@ -36,15 +41,33 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         .split(middle_section_area);
 
     let left_toolbar_area = middle_layout[0];
-    let main_area = middle_layout[1];
     let right_toolbar_area = middle_layout[2];
 
-    // RENDER THE VIEWPORT
-    let image_width = app.hdim_image.width;
-    let image_height = app.hdim_image.height;
+    // The histogram panel takes the bottom of the main area while it is shown
+    let (main_area, histogram_area) = if app.histogram_source.is_some() {
+        let [main_area, histogram_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(HISTOGRAM_HEIGHT)])
+                .areas(middle_layout[1]);
+        (main_area, Some(histogram_area))
+    } else {
+        (middle_layout[1], None)
+    };
 
-    let source_width = (main_area.width as f32 * app.zoom).round() as u32;
-    let source_height = (main_area.height as f32 * app.zoom * 2.0).round() as u32;
+    // RENDER THE VIEWPORT
+    let image_width = app.display_image.width;
+    let image_height = app.display_image.height;
+
+    // The image is drawn inside the borders of the main block
+    let main_block = Block::default().borders(Borders::ALL);
+    let image_area = main_block.inner(main_area);
+    app.viewport = Size {
+        width: image_area.width as u32,
+        height: image_area.height as u32,
+    };
+    let Size {
+        width: source_width,
+        height: source_height,
+    } = viewport_source_size(app.viewport, app.zoom);
 
     app.source_pos.0 = app
         .source_pos
@@ -60,13 +83,35 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         source_y: app.source_pos.1,
         source_width,
         source_height,
-        target_width: main_area.width as u32,
-        target_height: main_area.height as u32,
+        target_width: app.viewport.width,
+        target_height: app.viewport.height,
+    };
+
+    // Pending adjustments are previewed on the visible part only
+    let cell_pixels = match app.graphics {
+        Some(_) => graphics::cell_size(),
+        None => app.renderer.cell_pixels(),
+    };
+    app.update_adjust_preview(view, cell_pixels);
+    let (shown_image, view) = match &app.adjust_preview {
+        Some(preview) => (&preview.image, preview.view),
+        None => (&app.display_image, view),
     };
 
-    let image_text = match hdim_render::render(&app.hdim_image.data, &view) {
-        Result::Ok(ansi_string) => ansi_string.into_text().unwrap_or_default(),
-        Err(_) => "Error rendering image".into_text().unwrap(),
+    // Without a graphics backend the image is drawn with characters
+    let image_widget = if let Some(graphics) = &mut app.graphics {
+        // Left blank here and drawn after the frame by `GraphicsViewport::draw`
+        graphics.area = image_area;
+        graphics.view = Some(view);
+        None
+    } else {
+        Some(ImageWidget::cached(
+            &mut app.render_cache,
+            app.renderer.as_ref(),
+            shown_image,
+            app.display_generation,
+            &view,
+        ))
     };
 
     let magnification = 1.0 / app.zoom;
@@ -83,15 +128,27 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     );
 
     // Render Left Toolbar
-    let tools = List::new([ListItem::new("1. Crop"), ListItem::new("2. Exif")])
-        .block(Block::default().borders(Borders::ALL).title("Tools"));
+    let tools = List::new([
+        ListItem::new("1. Crop"),
+        ListItem::new("2. Exif"),
+        ListItem::new("3. Transform"),
+        ListItem::new("4. Resize"),
+        ListItem::new("5. Adjust"),
+    ])
+    .block(Block::default().borders(Borders::ALL).title("Tools"));
     frame.render_widget(tools, left_toolbar_area);
 
     // Render Main Content
-    frame.render_widget(
-        Paragraph::new(image_text).block(Block::default().borders(Borders::ALL).title(main_title)),
-        main_area,
-    );
+    frame.render_widget(main_block.title(main_title), main_area);
+    match image_widget {
+        Some(Result::Ok(widget)) => frame.render_widget(&widget, image_area),
+        Some(Err(_)) => frame.render_widget(Paragraph::new("Error rendering image"), image_area),
+        None => {}
+    }
+
+    if let Some(histogram_area) = histogram_area {
+        render_histogram(frame, histogram_area, app);
+    }
 
     // Render Right Toolbar (if visible)
     if app.show_right_toolbar {
@@ -112,17 +169,25 @@ pub fn render(frame: &mut Frame, app: &mut App) {
                     );
                 }
             }
-            AppMode::Normal | AppMode::EditingCropValue => {
-                if let Some(Tool::Crop) = app.selected_tool {
-                    frame.render_widget(render_crop_options(app), right_toolbar_area);
-                } else {
-                    frame.render_widget(
-                        List::new(vec![ListItem::new("Right Toolbar Content")])
-                            .block(Block::default().borders(Borders::ALL).title("Right")),
-                        right_toolbar_area,
-                    );
+            AppMode::Normal
+            | AppMode::EditingCropValue
+            | AppMode::EditingTransformValue
+            | AppMode::EditingResizeValue
+            | AppMode::SavingAs => match app.selected_tool {
+                Some(Tool::Crop) => {
+                    frame.render_widget(render_crop_options(app), right_toolbar_area)
                 }
-            }
+                Some(Tool::Transform) => {
+                    frame.render_widget(render_transform_options(app), right_toolbar_area)
+                }
+                Some(Tool::Resize) => {
+                    frame.render_widget(render_resize_options(app), right_toolbar_area)
+                }
+                Some(Tool::Adjust) => {
+                    frame.render_widget(render_adjust_options(app), right_toolbar_area)
+                }
+                _ => frame.render_widget(render_history(app), right_toolbar_area),
+            },
         };
     } else {
         // Render an empty block if the right toolbar is not explicitly shown
@@ -131,15 +196,26 @@ pub fn render(frame: &mut Frame, app: &mut App) {
 
     // Render Bottom Navigation Bar
     let bottom_text = match app.mode {
+        AppMode::SavingAs => format!("Save as: {}", app.save_input),
+        _ if app.status.is_some() => app.status.clone().unwrap_or_default(),
+        AppMode::Normal if app.selected_tool == Some(Tool::Adjust) => {
+            "Left/Right to change, with Shift in fine steps | Tab to switch | Enter to reset/apply | Esc to deselect".to_string()
+        }
         AppMode::Normal if app.selected_tool.is_some() => {
-            "Tab to switch | Enter to edit/select | Esc to deselect"
+            "Tab to switch | Enter to edit/select | 's' to Save as | Esc to deselect".to_string()
         }
-        AppMode::ExifView => "Up/Down to scroll | Esc to deselect",
-        _ => " Arrows to Pan | PgUp/PgDn to Zoom | 'q' to Quit ",
+        AppMode::ExifView => "Up/Down to scroll | Esc to deselect".to_string(),
+        _ => " Arrows to Pan | PgUp/PgDn to Zoom | 'u'/'r' to Undo/Redo | 'b' Background | 'o' Orientation | 'h' Histogram | 's' to Save as | 'q' to Quit "
+            .to_string(),
+    };
+    let bottom_title = match app.mode {
+        AppMode::SavingAs => "Enter to save | Esc to cancel",
+        _ => "Bottom",
     };
 
     frame.render_widget(
-        Paragraph::new(bottom_text).block(Block::default().borders(Borders::ALL).title("Bottom")),
+        Paragraph::new(bottom_text)
+            .block(Block::default().borders(Borders::ALL).title(bottom_title)),
         bottom_nav_area,
     );
 }
@@ -0,0 +1,369 @@
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("hdim_cli_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_image(path: &PathBuf, w: u32, h: u32) {
+    let mut buffer = RgbaImage::new(w, h);
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        *pixel = Rgba([x as u8, y as u8, 0, 255]);
+    }
+    DynamicImage::ImageRgba8(buffer).save(path).unwrap();
+}
+
+fn hdim() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_hdim"))
+}
+
+#[test]
+fn test_edit_applies_operations_in_order() {
+    let dir = temp_dir("order");
+    let input = dir.join("in.png");
+    let output = dir.join("out.png");
+    write_image(&input, 40, 20);
+
+    // Crop to 20x10 first, then rotate to 10x20, then resize the width only
+    let status = hdim()
+        .args(["edit", input.to_str().unwrap()])
+        .args(["--crop", "10,10,5,5", "--rotate", "90", "--resize", "5x20"])
+        .args(["-o", output.to_str().unwrap()])
+        .status()
+        .unwrap();
+
+    let edited = image::open(&output).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.success());
+    assert_eq!(edited.dimensions(), (5, 20));
+}
+
+#[test]
+fn test_edit_resize_modes_keep_the_aspect_ratio() {
+    let dir = temp_dir("resize_mode");
+    let input = dir.join("in.png");
+    write_image(&input, 40, 20);
+
+    let mut sizes = Vec::new();
+    for mode in ["fit", "fill"] {
+        let output = dir.join(format!("{}.png", mode));
+        let status = hdim()
+            .args(["edit", input.to_str().unwrap()])
+            .args(["--resize", "10x10", "--resize-mode", mode])
+            .args(["-o", output.to_str().unwrap()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        sizes.push(image::open(&output).unwrap().dimensions());
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(sizes, [(10, 5), (10, 10)]);
+}
+
+#[test]
+fn test_edit_reads_inputs_from_stdin() {
+    let dir = temp_dir("stdin");
+    let out_dir = dir.join("out");
+    let inputs = [dir.join("a.png"), dir.join("b.png")];
+    for input in &inputs {
+        write_image(input, 8, 6);
+    }
+
+    let mut child = hdim()
+        .args(["edit", "-", "--flip", "h", "--format", "bmp", "-d"])
+        .arg(&out_dir)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    for input in &inputs {
+        writeln!(child.stdin.as_mut().unwrap(), "{}", input.display()).unwrap();
+    }
+    drop(child.stdin.take());
+    let status = child.wait().unwrap();
+
+    let written = [out_dir.join("a.bmp"), out_dir.join("b.bmp")];
+    let all_written = written.iter().all(|path| path.exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.success());
+    assert!(all_written);
+}
+
+#[test]
+fn test_edit_reports_failures_with_exit_code() {
+    let dir = temp_dir("failure");
+    let good = dir.join("good.png");
+    write_image(&good, 4, 4);
+
+    let output = hdim()
+        .args([
+            "edit",
+            good.to_str().unwrap(),
+            "missing.png",
+            "--invert",
+            "-d",
+        ])
+        .arg(dir.join("out"))
+        .output()
+        .unwrap();
+
+    let good_written = dir.join("out").join("good.png").exists();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(good_written);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing.png"));
+}
+
+#[test]
+fn test_edit_rejects_output_with_several_inputs() {
+    let output = hdim()
+        .args(["edit", "a.png", "b.png", "-o", "out.png"])
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_edit_rejects_inputs_with_the_same_output() {
+    let dir = temp_dir("collision");
+    let inputs = [dir.join("a").join("img.png"), dir.join("b").join("img.png")];
+    for input in &inputs {
+        std::fs::create_dir_all(input.parent().unwrap()).unwrap();
+        write_image(input, 4, 4);
+    }
+
+    let output = hdim()
+        .arg("edit")
+        .args(&inputs)
+        .args(["--invert", "-d"])
+        .arg(dir.join("out"))
+        .output()
+        .unwrap();
+
+    let anything_written = dir.join("out").exists();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!anything_written);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("would both be written"));
+}
+
+#[test]
+fn test_edit_does_not_overwrite_inputs_unless_asked() {
+    let dir = temp_dir("overwrite");
+    let input = dir.join("img.png");
+    write_image(&input, 4, 4);
+    let edit = |overwrite: bool| {
+        let mut command = hdim();
+        command
+            .args(["edit", input.to_str().unwrap(), "--flip", "h", "-d"])
+            // The same directory, spelled differently
+            .arg(dir.join(".").join("..").join(dir.file_name().unwrap()));
+        if overwrite {
+            command.arg("--overwrite");
+        }
+        command.output().unwrap()
+    };
+
+    let refused = edit(false);
+    let unchanged = image::open(&input).unwrap().get_pixel(0, 0);
+    let overwritten = edit(true);
+    let flipped = image::open(&input).unwrap().get_pixel(0, 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(refused.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("--overwrite"));
+    assert_eq!(unchanged, Rgba([0, 0, 0, 255]));
+    assert!(overwritten.status.success());
+    assert_eq!(flipped, Rgba([3, 0, 0, 255]));
+}
+
+#[test]
+fn test_edit_rejects_stdin_given_twice() {
+    let output = hdim()
+        .args(["edit", "-", "-", "-d", "out"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_show_prints_requested_size() {
+    let dir = temp_dir("show");
+    let input = dir.join("in.png");
+    write_image(&input, 40, 20);
+
+    let output = hdim()
+        .args(["show", input.to_str().unwrap(), "--width", "20"])
+        .output()
+        .unwrap();
+    let print_output = hdim()
+        .args(["--print", input.to_str().unwrap()])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(output.status.success());
+    // 40x20 pixels at 20 columns is 20x10 pixels, or 5 rows of half blocks
+    assert_eq!(lines.len(), 5);
+    assert!(lines.iter().all(|line| line.matches('▄').count() == 20));
+    // Without a terminal the image is printed at its natural size
+    assert_eq!(
+        String::from_utf8(print_output.stdout)
+            .unwrap()
+            .lines()
+            .count(),
+        10
+    );
+}
+
+#[test]
+fn test_show_with_reduced_palette() {
+    let dir = temp_dir("show_ansi16");
+    let input = dir.join("in.png");
+    write_image(&input, 8, 4);
+
+    let output = hdim()
+        .args([
+            "show",
+            input.to_str().unwrap(),
+            "--backend",
+            "ansi16",
+            "--dither",
+            "ordered",
+        ])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert_eq!(stdout.lines().count(), 2);
+    assert!(
+        !stdout.contains(";2;"),
+        "no truecolor escapes: {:?}",
+        stdout
+    );
+}
+
+#[test]
+fn test_show_ascii_has_no_escape_codes() {
+    let dir = temp_dir("show_ascii");
+    let input = dir.join("in.png");
+    write_image(&input, 20, 10);
+
+    let output = hdim()
+        .args(["show", input.to_str().unwrap(), "--backend", "ascii"])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert_eq!(stdout.lines().count(), 5);
+    assert!(!stdout.contains('\x1b'));
+}
+
+#[test]
+fn test_show_composites_transparent_pixels_over_background() {
+    let dir = temp_dir("show_background");
+    let input = dir.join("in.png");
+    // Fully transparent, with stray color left under the alpha
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 2, Rgba([255, 0, 255, 0])))
+        .save(&input)
+        .unwrap();
+
+    let output = hdim()
+        .args([
+            "show",
+            input.to_str().unwrap(),
+            "--backend",
+            "truecolor",
+            "--background",
+            "#1e1e2e",
+        ])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert!(stdout.contains("\x1b[48;2;30;30;46m"), "{:?}", stdout);
+    assert!(!stdout.contains("255;0;255"), "{:?}", stdout);
+}
+
+#[test]
+fn test_show_rejects_signed_background_colors() {
+    let dir = temp_dir("show_signed_background");
+    let input = dir.join("in.png");
+    write_image(&input, 4, 2);
+
+    let output = hdim()
+        .args(["show", input.to_str().unwrap(), "--background", "+12345"])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("hex color"));
+}
+
+fn orientation_fixture(tag: u8) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join(format!("../hdim-core/tests/images/orientation/{}.jpg", tag))
+}
+
+#[test]
+fn test_show_turns_images_upright_unless_raw() {
+    let input = orientation_fixture(6);
+    let width = |raw: bool| {
+        let mut command = hdim();
+        command.args(["show", input.to_str().unwrap(), "--backend", "ascii"]);
+        if raw {
+            command.arg("--raw-orientation");
+        }
+        let output = command.output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+
+    // Stored 32x48 and rotated a quarter turn by the EXIF orientation
+    assert_eq!(width(false), 48);
+    assert_eq!(width(true), 32);
+}
+
+#[test]
+fn test_edit_writes_upright_pixels() {
+    let dir = temp_dir("orientation");
+    let output = dir.join("out.png");
+
+    let status = hdim()
+        .args(["edit", orientation_fixture(8).to_str().unwrap(), "-o"])
+        .arg(&output)
+        .status()
+        .unwrap();
+    let written = image::open(&output).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.success());
+    assert_eq!(written.dimensions(), (48, 32));
+}